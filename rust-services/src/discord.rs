@@ -1,3 +1,4 @@
 pub mod client;
 pub mod commands;
+pub mod delivery;
 pub mod handlers;
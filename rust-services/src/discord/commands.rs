@@ -1,4 +1,30 @@
 pub mod auto_manga_commands;
 pub mod channels_commands;
+pub mod digest_commands;
+pub mod dm_commands;
 pub mod manga_commands;
-pub mod scrape_manga_commands;
+pub mod settings_commands;
+pub mod source_commands;
+pub mod subscription_commands;
+pub mod user_commands;
+
+use serenity::all::{CommandDataOptionValue, CommandInteraction};
+
+// ดึงค่าของ option ภายในคำสั่งย่อย
+pub fn get_sub_option<'a>(
+    command: &'a CommandInteraction,
+    subcommand: &str,
+    name: &str,
+) -> Option<&'a CommandDataOptionValue> {
+    command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == subcommand)
+        .and_then(|opt| match &opt.value {
+            CommandDataOptionValue::SubCommand(sub_opts) => Some(sub_opts),
+            _ => None,
+        })
+        .and_then(|sub_opts| sub_opts.iter().find(|opt| opt.name == name))
+        .map(|opt| &opt.value)
+}
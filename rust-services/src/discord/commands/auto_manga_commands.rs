@@ -1,10 +1,70 @@
-use crate::discord::commands::scrape_manga_commands::scrape_manga_sing_manga;
-use crate::models::manga::Manga;
+use crate::discord::commands::dm_commands;
+use crate::discord::delivery::{self, Branding, OutgoingMessage};
+use crate::models::channels::{Channel, DeliveryMode};
+use crate::models::chapter::Chapter;
+use crate::models::guild_subscription::GuildSubscription;
+use crate::models::manga::{Manga, DORMANT_CHECK_INTERVAL};
+use crate::models::pending_notification::PendingNotification;
+use crate::models::release_pattern::ReleasePattern;
 use crate::service::channels_service::ChannelsService;
+use crate::service::chapter_service::ChapterService;
+use crate::service::guild_settings_service::GuildSettingsService;
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_role_service::MangaRoleService;
 use crate::service::manga_service::MangaService;
-use serenity::all::{ChannelId, Colour, Context, CreateEmbed, CreateEmbedFooter, CreateMessage};
+use crate::service::pending_notification_service::PendingNotificationService;
+use crate::service::user_subscription_service::UserSubscriptionService;
+use crate::sources::{self, executor, ScrapeError, ScrapeResult, ScrapedChapter};
+use crate::utils::time::discord_timestamp;
+use futures::stream::{self, StreamExt};
+use mongodb::bson::oid::ObjectId;
+use serenity::all::{
+    ButtonStyle, ChannelId, Colour, Context, CreateActionRow, CreateAllowedMentions, CreateButton,
+    CreateEmbed, CreateEmbedFooter, CreateMessage, RoleId, UserId,
+};
+use std::collections::{HashMap, HashSet};
 use tokio::time;
 
+// จำนวนตอนใหม่สูงสุดที่จะแสดงลิงก์ครบทุกตอน ถ้ามากกว่านี้จะส่งเป็นข้อความสรุป
+const MAX_LISTED_CHAPTERS: usize = 5;
+// จำนวนลิงก์ตอนล่าสุดที่แสดงในข้อความสรุป
+const SUMMARY_LINKED_CHAPTERS: usize = 3;
+// ความยาวสูงสุดของ custom_id และข้อความบนปุ่มที่ Discord รับได้
+const MAX_CUSTOM_ID_LENGTH: usize = 100;
+const MAX_BUTTON_LABEL_LENGTH: usize = 80;
+// ความถี่ที่ตัวตั้งเวลาตรวจหามังงะที่ถึงเวลาเช็ค
+const SCHEDULER_TICK: time::Duration = time::Duration::from_secs(60);
+// จำนวนมังงะสูงสุดที่เช็คในหนึ่งรอบ ที่เหลือจะถูกเช็คในรอบถัดไป
+const MAX_CHECKS_PER_TICK: i64 = 50;
+// ค่าเริ่มต้นของจำนวนชั่วโมงที่เช็คเรื่องหนึ่งไม่สำเร็จติดต่อกันก่อนแจ้งเตือนผู้ดูแล
+const DEFAULT_FAILURE_ALERT_HOURS: i64 = 24;
+// ความยาวสูงสุดของข้อผิดพลาดที่แสดงในการแจ้งเตือน (Discord จำกัดค่าของ field ที่ 1024 ตัวอักษร)
+const MAX_ERROR_LENGTH: usize = 1000;
+
+// ช่องสำหรับแจ้งเตือนผู้ดูแลระบบ (ADMIN_CHANNEL_ID) ถ้าไม่กำหนดจะไม่แจ้งเตือน
+fn admin_channel_id() -> Option<ChannelId> {
+    std::env::var("ADMIN_CHANNEL_ID")
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(ChannelId::new)
+}
+
+// เช็คไม่สำเร็จติดต่อกันนานกว่ากี่ชั่วโมงจึงแจ้งเตือนผู้ดูแล (SCRAPE_FAILURE_ALERT_HOURS)
+fn failure_alert_hours() -> i64 {
+    std::env::var("SCRAPE_FAILURE_ALERT_HOURS")
+        .ok()
+        .and_then(|hours| hours.trim().parse::<i64>().ok())
+        .unwrap_or(DEFAULT_FAILURE_ALERT_HOURS)
+}
+
+// การอัพเดทของมังงะหนึ่งเรื่อง พร้อมตอนใหม่ทั้งหมดที่พบในรอบนี้ (เรียงจากเก่าไปใหม่)
+pub struct MangaUpdate {
+    pub manga: Manga,
+    pub new_chapters: Vec<ScrapedChapter>,
+}
+
 // โครงสร้างสำหรับจัดการคำสั่งอัพเดทมังงะอัตโนมัติ
 pub struct AutoMangaCommands;
 
@@ -13,249 +73,581 @@ impl AutoMangaCommands {
         AutoMangaCommands
     }
 
-    // ส่งข้อความอัพเดทไปยังช่องที่กำหนด
-    async fn send_update_to_all_channels(ctx: &Context, mangas: Vec<Manga>) {
+    // สร้าง embed ของการอัพเดท ตอนใหม่ไม่เกิน MAX_LISTED_CHAPTERS จะแสดงลิงก์ทุกตอน
+    // ถ้ามากกว่านั้นจะสรุปช่วงตอนและแสดงเฉพาะลิงก์ตอนล่าสุด
+    fn build_update_embed(update: &MangaUpdate) -> CreateEmbed {
+        let manga = &update.manga;
+        let chapters = &update.new_chapters;
+
+        let chapter_link =
+            |chapter: &ScrapedChapter| format!("• [ตอนที่ {}]({})", chapter.number, chapter.url);
+
+        let description = match chapters.as_slice() {
+            [] | [_] => format!("อัพเดทถึงตอนที่ {}", manga.latest_chapter),
+            [first, .., last] if chapters.len() <= MAX_LISTED_CHAPTERS => format!(
+                "มีตอนใหม่ {} ตอน (ตอนที่ {} - {})\n{}",
+                chapters.len(),
+                first.number,
+                last.number,
+                chapters
+                    .iter()
+                    .map(chapter_link)
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            [first, .., last] => format!(
+                "มีตอนใหม่ {} ตอน (ตอนที่ {} - {})\nตอนล่าสุด:\n{}\n\n[ดูตอนทั้งหมด]({})",
+                chapters.len(),
+                first.number,
+                last.number,
+                chapters
+                    .iter()
+                    .rev()
+                    .take(SUMMARY_LINKED_CHAPTERS)
+                    .map(chapter_link)
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                manga.url
+            ),
+        };
+
+        let embed = CreateEmbed::new()
+            .title(format!("การอัพเดทมังงะ: {}", manga.title))
+            .description(description)
+            .field("ชื่อมังงะ", &manga.title, true)
+            .field("ตอนล่าสุด", format!("ตอนที่ {}", manga.latest_chapter), true)
+            .field("ลิงก์ตอนล่าสุด", &manga.latest_chapter_url, false)
+            .field("เวลาอัพเดท", discord_timestamp(&manga.updated_at), true)
+            .color(Colour::DARK_GREEN)
+            .footer(CreateEmbedFooter::new("ระบบอัพเดทมังงะอัตโนมัติ"));
+
+        if let Some(image_url) = &manga.image_url {
+            embed.thumbnail(image_url)
+        } else {
+            embed
+        }
+    }
+
+    // ข้อความอัพเดทพร้อมปุ่มบันทึกการอ่านและปุ่มเปิดตอนล่าสุด
+    // ปุ่มบันทึกการอ่านใช้ custom_id รูปแบบ manga_read:<รหัสมังงะ>:<เลขตอน>
+    // ถ้าเลขตอนยาวจน custom_id เกินที่ Discord รับได้จะไม่ใส่ปุ่มนี้ เพื่อไม่ให้ข้อความทั้งข้อความส่งไม่สำเร็จ
+    // ถ้าเซิร์ฟเวอร์ผูก role กับมังงะไว้ จะ mention role นั้นเท่านั้นและมีปุ่มรับ role
+    fn build_update_message(update: &MangaUpdate, role_id: Option<RoleId>) -> OutgoingMessage {
+        let manga = &update.manga;
+        let (latest_number, latest_url) = match update.new_chapters.last() {
+            Some(chapter) => (&chapter.number, &chapter.url),
+            None => (&manga.latest_chapter, &manga.latest_chapter_url),
+        };
+
+        let mut buttons = Vec::new();
+        if let Some(manga_id) = &manga.id {
+            let custom_id = format!("manga_read:{}:{latest_number}", manga_id.to_hex());
+            if custom_id.chars().count() <= MAX_CUSTOM_ID_LENGTH {
+                buttons.push(
+                    CreateButton::new(custom_id)
+                        .label("อ่านแล้ว")
+                        .emoji('✅')
+                        .style(ButtonStyle::Success),
+                );
+            }
+        }
+        buttons.push(
+            CreateButton::new_link(latest_url).label(
+                format!("อ่านตอนที่ {latest_number}")
+                    .chars()
+                    .take(MAX_BUTTON_LABEL_LENGTH)
+                    .collect::<String>(),
+            ),
+        );
+
+        let message = OutgoingMessage::new(Self::build_update_embed(update));
+        match (role_id, &manga.id) {
+            (Some(role_id), Some(manga_id)) => {
+                buttons.push(
+                    CreateButton::new(format!("subscription_role:{}", manga_id.to_hex()))
+                        .label("รับ/เลิกรับแจ้งเตือน")
+                        .emoji('🔔')
+                        .style(ButtonStyle::Secondary),
+                );
+                message
+                    .content(format!("<@&{role_id}>"))
+                    .allowed_mentions(CreateAllowedMentions::new().roles(vec![role_id]))
+                    .components(vec![CreateActionRow::Buttons(buttons)])
+            }
+            _ => message
+                .allowed_mentions(CreateAllowedMentions::new())
+                .components(vec![CreateActionRow::Buttons(buttons)]),
+        }
+    }
+
+    // ช่องที่ต้องส่งการอัพเดทของมังงะเรื่องนี้ ตามการติดตามของแต่ละเซิร์ฟเวอร์
+    // การติดตามที่ระบุช่องจะส่งไปช่องนั้น ไม่เช่นนั้นจะส่งทุกช่องของเซิร์ฟเวอร์ที่ตัวกรองยอมรับ
+    fn subscribed_channels<'a>(
+        manga: &Manga,
+        subscriptions: &[GuildSubscription],
+        channels: &'a [Channel],
+    ) -> Vec<&'a Channel> {
+        let mut subscribed: Vec<&Channel> = Vec::new();
+        for subscription in subscriptions {
+            let targets = channels.iter().filter(|channel| {
+                channel.guild_id == subscription.guild_id
+                    && match &subscription.channel_id {
+                        Some(channel_id) => &channel.channel_id == channel_id,
+                        None => channel.accepts(manga),
+                    }
+            });
+
+            for channel in targets {
+                if !subscribed
+                    .iter()
+                    .any(|c| c.channel_id == channel.channel_id)
+                {
+                    subscribed.push(channel);
+                }
+            }
+        }
+        subscribed
+    }
+
+    // ส่งข้อความอัพเดทไปยังช่องของเซิร์ฟเวอร์ที่ติดตามมังงะแต่ละเรื่อง
+    async fn send_update_to_subscribers(ctx: &Context, updates: &[MangaUpdate]) {
         // ดึงข้อมูลช่อง
-        let channels: Vec<crate::models::channels::Channel> = match ChannelsService::get_all_channels().await {
+        let channels: Vec<Channel> = match ChannelsService::get_all_channels().await {
             Ok(channels) => channels,
             Err(e) => {
-                println!("เกิดข้อผิดพลาดในการดึง channels: {:?}", e);
+                println!("เกิดข้อผิดพลาดในการดึง channels: {e:?}");
                 return;
             }
         };
 
-        let channel_ids: Vec<ChannelId> = channels
-            .into_iter()
-            .filter_map(|channel| channel.channel_id.parse::<u64>().ok().map(ChannelId::new))
-            .collect();
-
-        if channel_ids.is_empty() {
+        if channels.is_empty() {
             println!("ไม่พบช่องสำหรับการอัพเดทมังงะในฐานข้อมูล");
             return;
         }
-        println!("channel_ids: {:?}", channel_ids);
+
+        let settings = match GuildSettingsService::get_all().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                println!("เกิดข้อผิดพลาดในการดึงการตั้งค่าเซิร์ฟเวอร์: {e:?}");
+                HashMap::new()
+            }
+        };
+        let now = chrono::Utc::now();
 
         // ส่งข้อมูลทุกมังงะ
-        for manga in mangas {
-            let embed = CreateEmbed::new()
-                .title(format!("การอัพเดทมังงะ: {}", manga.title))
-                .description(format!("อัพเดทถึงตอนที่ {}", manga.latest_chapter))
-                .field("ชื่อมังงะ", &manga.title, true)
-                .field("ตอนล่าสุด", format!("ตอนที่ {}", manga.latest_chapter), true)
-                .field("ลิงก์ตอนล่าสุด", &manga.latest_chapter_url, false)
-                .field(
-                    "เวลาอัพเดท",
-                    chrono::DateTime::<chrono::Utc>::from(manga.updated_at.to_system_time())
-                        .format("%d/%m/%Y %H:%M:%S")
-                        .to_string(),
-                    true,
-                )
-                .color(Colour::DARK_GREEN)
-                .footer(CreateEmbedFooter::new("ระบบอัพเดทมังงะอัตโนมัติ"));
+        for update in updates {
+            let Some(manga_id) = &update.manga.id else {
+                continue;
+            };
+            let subscriptions = match GuildSubscriptionService::get_by_manga(manga_id).await {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    println!(
+                        "เกิดข้อผิดพลาดในการดึงการติดตามของ {}: {e:?}",
+                        update.manga.title
+                    );
+                    continue;
+                }
+            };
 
-            let embed = if let Some(image_url) = &manga.image_url {
-                embed.thumbnail(image_url)
-            } else {
-                embed
+            let subscribed = Self::subscribed_channels(&update.manga, &subscriptions, &channels);
+            if subscribed.is_empty() {
+                println!("ไม่มีเซิร์ฟเวอร์ที่ติดตาม {}", update.manga.title);
+                continue;
+            }
+
+            let roles = match MangaRoleService::get_by_manga(manga_id).await {
+                Ok(roles) => roles,
+                Err(e) => {
+                    println!("เกิดข้อผิดพลาดในการดึง role ของ {}: {e:?}", update.manga.title);
+                    Vec::new()
+                }
             };
 
-            for channel_id in &channel_ids {
-                let message = CreateMessage::new().add_embed(embed.clone());
+            let branding = Branding::for_manga(&update.manga);
+            let mut pending = Vec::new();
+            for channel in subscribed {
+                // ช่องที่ใช้โหมดสรุปจะเก็บตอนใหม่ไว้ส่งพร้อมกันตามรอบ
+                // และระหว่างช่วงงดแจ้งเตือนจะพักไว้ส่งเมื่อพ้นช่วงแล้ว
+                let quiet = settings
+                    .get(&channel.guild_id)
+                    .is_some_and(|settings| settings.is_quiet_at(now));
+                if channel.delivery_mode != DeliveryMode::Immediate || quiet {
+                    pending.extend(update.new_chapters.iter().map(|chapter| {
+                        PendingNotification::new(
+                            channel.guild_id.clone(),
+                            channel.channel_id.clone(),
+                            *manga_id,
+                            update.manga.title.clone(),
+                            chapter.number.clone(),
+                            chapter.url.clone(),
+                        )
+                    }));
+                    continue;
+                }
+
+                let role_id = roles
+                    .iter()
+                    .find(|role| role.guild_id == channel.guild_id)
+                    .and_then(|role| role.role_id.parse::<u64>().ok())
+                    .map(RoleId::new);
+                let message = Self::build_update_message(update, role_id);
 
-                if let Err(why) = channel_id.send_message(&ctx.http, message).await {
-                    println!("เกิดข้อผิดพลาดในการส่งข้อความไปยังช่อง {}: {:?}", channel_id, why);
+                if let Err(why) = delivery::send_manga_message(
+                    ctx,
+                    channel,
+                    manga_id,
+                    &update.manga.title,
+                    &message,
+                    &branding,
+                )
+                .await
+                {
+                    println!(
+                        "เกิดข้อผิดพลาดในการส่งข้อความไปยังช่อง {}: {why:?}",
+                        channel.channel_id
+                    );
                 }
             }
+
+            if let Err(e) = PendingNotificationService::enqueue(&pending).await {
+                println!("เกิดข้อผิดพลาดในการบันทึกตอนที่รอส่งในสรุป: {e:?}");
+            }
         }
         println!("ส่งข้อความอัพเดทสำเร็จ!");
     }
 
-    // ฟังก์ชันสำหรับการอัพเดทแบบเป็นระยะ (ทุก 4 ชั่วโมง)
+    // ส่งข้อความอัพเดทให้ผู้ใช้ที่ติดตามมังงะทาง DM
+    // ผู้ใช้ที่ปิดรับ DM จะถูกหยุดส่งจนกว่าจะติดตามใหม่อีกครั้ง
+    async fn send_update_to_users(ctx: &Context, updates: &[MangaUpdate]) {
+        for update in updates {
+            let Some(manga_id) = &update.manga.id else {
+                continue;
+            };
+            let subscriptions = match UserSubscriptionService::get_active_by_manga(manga_id).await {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    println!(
+                        "เกิดข้อผิดพลาดในการดึงผู้ติดตามทาง DM ของ {}: {e:?}",
+                        update.manga.title
+                    );
+                    continue;
+                }
+            };
+
+            let message = Self::build_update_message(update, None).to_message();
+
+            for subscription in subscriptions {
+                let Ok(user_id) = subscription.user_id.parse::<u64>() else {
+                    continue;
+                };
+                let message = message.clone();
+
+                match UserId::new(user_id)
+                    .direct_message(&ctx.http, message)
+                    .await
+                {
+                    Ok(_) => {}
+                    Err(why) if dm_commands::is_dm_closed_error(&why) => {
+                        println!("ผู้ใช้ {user_id} ปิดรับ DM หยุดส่งแจ้งเตือนทาง DM");
+                        if let Err(e) =
+                            UserSubscriptionService::mark_dm_closed(&subscription.user_id).await
+                        {
+                            println!("เกิดข้อผิดพลาดในการหยุดการแจ้งเตือนทาง DM: {e:?}");
+                        }
+                    }
+                    Err(why) => {
+                        println!("เกิดข้อผิดพลาดในการส่ง DM ไปยังผู้ใช้ {user_id}: {why:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    // เทียบรายการตอนที่ดึงมากับประวัติในฐานข้อมูล แล้วคืนตอนที่ยังไม่เคยพบ (เรียงจากเก่าไปใหม่)
+    // ถ้ายังไม่มีประวัติ (มังงะที่เพิ่มก่อนมีระบบประวัติ) จะใช้เฉพาะตอนที่ใหม่กว่าตอนล่าสุดที่บันทึกไว้
+    async fn detect_new_chapters(
+        manga: &Manga,
+        result: &ScrapeResult,
+    ) -> Result<Vec<ScrapedChapter>, mongodb::error::Error> {
+        let history: Vec<Chapter> = match &manga.id {
+            Some(manga_id) => ChapterService::get_by_manga(manga_id).await?,
+            None => Vec::new(),
+        };
+
+        if history.is_empty() {
+            return Ok(result
+                .chapters
+                .iter()
+                .filter(|chapter| chapter.number > manga.latest_chapter)
+                .cloned()
+                .collect());
+        }
+
+        // ตอนที่เลขตอนเคยพบแล้วแต่ลิงก์เปลี่ยนไม่นับเป็นตอนใหม่
+        let known_urls: HashSet<&str> = history.iter().map(|c| c.url.as_str()).collect();
+        let known_numbers: HashSet<_> = history.iter().map(|c| &c.number).collect();
+
+        Ok(result
+            .chapters
+            .iter()
+            .filter(|chapter| {
+                !known_urls.contains(chapter.url.as_str())
+                    && !known_numbers.contains(&chapter.number)
+            })
+            .cloned()
+            .collect())
+    }
+
+    // บันทึกว่าเช็คสำเร็จ แล้วตั้งเวลาเช็คครั้งถัดไปตามรูปแบบการออกตอนที่เรียนรู้จากประวัติ
+    // เรื่องที่ประวัติยังน้อยจะใช้เวลาที่พบตอนใหม่ล่าสุดแทน (รวมตอนที่พบในรอบนี้)
+    async fn schedule_next_check(manga: &Manga, found_new: bool) {
+        let pattern = match &manga.id {
+            Some(manga_id) => ChapterService::get_release_times(manga_id)
+                .await
+                .ok()
+                .and_then(|times| ReleasePattern::learn(&times)),
+            None => None,
+        };
+
+        let check_interval = match pattern {
+            Some(pattern) => pattern.check_interval_at(chrono::Utc::now()),
+            None => {
+                let now = mongodb::bson::DateTime::now();
+                let last_release = match (&manga.id, found_new) {
+                    (_, true) => Some(now),
+                    (Some(manga_id), false) => ChapterService::last_seen_at(manga_id)
+                        .await
+                        .unwrap_or_default(),
+                    (None, false) => None,
+                };
+                Manga::check_interval_for(last_release, now)
+            }
+        };
+
+        if let Err(e) = MangaService::mark_checked(&manga.url, check_interval).await {
+            println!("เกิดข้อผิดพลาดในการบันทึกเวลาเช็คอัพเดท: {e:?}");
+        }
+    }
+
+    // เลื่อนการเช็คเรื่องที่เช็คไม่สำเร็จออกไปตามระยะเวลาที่กำหนด (วินาที)
+    async fn retry_later(manga: &Manga, delay: i64) {
+        let next_check_at = mongodb::bson::DateTime::from_millis(
+            mongodb::bson::DateTime::now().timestamp_millis() + delay * 1000,
+        );
+        if let Err(e) = MangaService::reschedule(&manga.url, next_check_at).await {
+            println!("เกิดข้อผิดพลาดในการเลื่อนเวลาเช็คอัพเดท: {e:?}");
+        }
+    }
+
+    // บันทึกว่าเช็คไม่สำเร็จ แล้วเลื่อนการเช็คออกไปนานขึ้นเรื่อย ๆ ตามจำนวนครั้งที่ไม่สำเร็จติดต่อกัน
+    // ถ้าเว็บไซต์ขอให้รอ (Retry-After) นานกว่านั้นจะเลื่อนออกไปตามที่เว็บไซต์ขอ
+    // ถ้าเช็คไม่สำเร็จนานเกินกำหนดจะแจ้งเตือนไปยังช่องของผู้ดูแลครั้งเดียวจนกว่าจะเช็คสำเร็จอีกครั้ง
+    async fn record_failure(ctx: &Context, manga: &Manga, error: &ScrapeError) {
+        let now = mongodb::bson::DateTime::now();
+        let consecutive_failures = manga.consecutive_failures + 1;
+        let failing_since = manga.failing_since.unwrap_or(now);
+        let backoff = Manga::failure_backoff(consecutive_failures, manga.check_interval);
+        let requested = sources::retry_after(error).map_or(0, |delay| delay.as_secs() as i64);
+        let next_check_at = mongodb::bson::DateTime::from_millis(
+            now.timestamp_millis() + backoff.max(requested) * 1000,
+        );
+        let error = error.to_string();
+        if let Err(e) =
+            MangaService::record_failure(&manga.url, failing_since, &error, next_check_at).await
+        {
+            println!("เกิดข้อผิดพลาดในการบันทึกการเช็คที่ไม่สำเร็จ: {e:?}");
+        }
+
+        let failing_for = (now.timestamp_millis() - failing_since.timestamp_millis()) / 1000;
+        if manga.failure_alerted || failing_for < failure_alert_hours() * 60 * 60 {
+            return;
+        }
+        let Some(channel_id) = admin_channel_id() else {
+            return;
+        };
+
+        let embed = CreateEmbed::new()
+            .title(format!("เช็คอัพเดทไม่สำเร็จ: {}", manga.title))
+            .url(&manga.url)
+            .field("ไม่สำเร็จติดต่อกัน", format!("{consecutive_failures} ครั้ง"), true)
+            .field("เริ่มไม่สำเร็จเมื่อ", discord_timestamp(&failing_since), true)
+            .field("เช็คครั้งถัดไป", discord_timestamp(&next_check_at), true)
+            .field(
+                "ข้อผิดพลาดล่าสุด",
+                error.chars().take(MAX_ERROR_LENGTH).collect::<String>(),
+                false,
+            )
+            .color(Colour::RED)
+            .footer(CreateEmbedFooter::new("ระบบอัพเดทมังงะอัตโนมัติ"));
+        match channel_id
+            .send_message(&ctx.http, CreateMessage::new().embed(embed))
+            .await
+        {
+            Ok(_) => {
+                if let Err(e) = MangaService::mark_failure_alerted(&manga.url).await {
+                    println!("เกิดข้อผิดพลาดในการบันทึกการแจ้งเตือนผู้ดูแล: {e:?}");
+                }
+            }
+            Err(e) => println!("เกิดข้อผิดพลาดในการแจ้งเตือนผู้ดูแล: {e:?}"),
+        }
+    }
+
+    // เช็คอัพเดทของมังงะหนึ่งเรื่อง คืนการอัพเดทถ้ามีตอนใหม่
+    async fn check_manga(
+        ctx: &Context,
+        manga: Manga,
+        scrape_run_id: &ObjectId,
+    ) -> Option<MangaUpdate> {
+        // เลือกอะแดปเตอร์ตาม host ของ URL
+        let Some(source) = sources::registry().find_by_url(&manga.url) else {
+            println!("ไม่มีอะแดปเตอร์รองรับมังงะ {} ({})", manga.title, manga.url);
+            Self::retry_later(&manga, DORMANT_CHECK_INTERVAL).await;
+            return None;
+        };
+
+        // เช็คอัพเดทจากเว็บไซต์
+        let result = match source.scrape(&manga.url).await {
+            Ok(result) => result,
+            Err(e) => {
+                println!(
+                    "เกิดข้อผิดพลาดในการเช็คอัพเดทมังงะ {} ผ่าน {}: {:?}",
+                    manga.title,
+                    source.name(),
+                    e
+                );
+                Self::record_failure(ctx, &manga, &e).await;
+                return None;
+            }
+        };
+
+        let Some(latest) = result.latest_chapter() else {
+            println!("ไม่พบตอนของมังงะ {}", result.title);
+            Self::schedule_next_check(&manga, false).await;
+            return None;
+        };
+
+        let new_chapters = match Self::detect_new_chapters(&manga, &result).await {
+            Ok(new_chapters) => new_chapters,
+            Err(e) => {
+                println!("เกิดข้อผิดพลาดในการตรวจสอบประวัติตอน: {e:?}");
+                Self::schedule_next_check(&manga, false).await;
+                return None;
+            }
+        };
+
+        // บันทึกประวัติตอนทั้งหมดที่พบ ก่อนตั้งเวลาเช็คครั้งถัดไปเพื่อให้นับการออกตอนรอบนี้ด้วย
+        if let Some(manga_id) = &manga.id {
+            if let Err(e) = ChapterService::record_chapters(
+                manga_id,
+                &result.title,
+                &result.chapters,
+                scrape_run_id,
+            )
+            .await
+            {
+                println!("เกิดข้อผิดพลาดในการบันทึกประวัติตอน: {e:?}");
+            }
+        }
+        Self::schedule_next_check(&manga, !new_chapters.is_empty()).await;
+
+        if new_chapters.is_empty() && latest.number <= manga.latest_chapter {
+            println!(
+                "{} ยังไม่มีการอัพเดทใหม่ (ตอนล่าสุด: {})",
+                result.title, manga.latest_chapter
+            );
+            return None;
+        }
+
+        // สร้างข้อมูลมังงะใหม่
+        let latest_chapter = latest.number.clone().max(manga.latest_chapter.clone());
+        let latest_chapter_url = if latest.number >= manga.latest_chapter {
+            latest.url.clone()
+        } else {
+            manga.latest_chapter_url.clone()
+        };
+        let updated_manga = Manga {
+            id: manga.id,
+            alt_titles: result.alt_titles.clone(),
+            created_at: manga.created_at,
+            ..Manga::new(
+                result.title.clone(),
+                manga.url,
+                latest_chapter,
+                latest_chapter_url,
+                result.cover_url.clone(),
+            )
+        };
+
+        // อัพเดทข้อมูลในฐานข้อมูล
+        if let Err(e) = MangaService::update(&updated_manga).await {
+            println!("เกิดข้อผิดพลาดในการอัพเดทข้อมูลมังงะ: {e:?}");
+            return None;
+        }
+
+        if new_chapters.is_empty() {
+            return None;
+        }
+
+        println!(
+            "พบการอัพเดทใหม่สำหรับ {}: {} ตอน (ล่าสุดตอนที่ {})",
+            updated_manga.title,
+            new_chapters.len(),
+            updated_manga.latest_chapter
+        );
+
+        Some(MangaUpdate {
+            manga: updated_manga,
+            new_chapters,
+        })
+    }
+
+    // ตรวจทุกนาทีว่ามีมังงะเรื่องใดถึงเวลาเช็คอัพเดทแล้ว
+    // เวลาเช็คครั้งถัดไปเก็บไว้ในฐานข้อมูล จึงไม่เช็คทุกเรื่องใหม่หมดเมื่อรีสตาร์ท
     pub async fn run_periodic_update(&self, ctx: &Context) {
         println!("เริ่มการทำงานอัพเดทอัตโนมัติ...");
-        let mut interval = time::interval(time::Duration::from_secs(4 * 60 * 60));
+        match MangaService::schedule_unscheduled().await {
+            Ok(0) => {}
+            Ok(scheduled) => println!("ตั้งเวลาเช็คอัพเดทให้มังงะที่ยังไม่มีเวลาเช็ค {scheduled} เรื่อง"),
+            Err(e) => println!("เกิดข้อผิดพลาดในการตั้งเวลาเช็คอัพเดท: {e:?}"),
+        }
+
+        let mut interval = time::interval(SCHEDULER_TICK);
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
 
         loop {
             interval.tick().await;
 
-            // ดึงข้อมูลมังงะทั้งหมดจากฐานข้อมูล
-            match MangaService::get_all().await {
+            // รหัสของรอบการดึงข้อมูลนี้ ใช้บันทึกว่าตอนไหนถูกพบในรอบใด
+            let scrape_run_id = ObjectId::new();
+
+            // ดึงเฉพาะมังงะที่ถึงเวลาเช็คแล้ว แล้วเช็คพร้อมกันหลายเรื่องโดยสลับกันทีละเว็บไซต์
+            // จำนวนคำขอพร้อมกันและอัตราการส่งต่อเว็บไซต์ถูกจำกัดที่ sources::fetch_html
+            match MangaService::get_due(mongodb::bson::DateTime::now(), MAX_CHECKS_PER_TICK).await {
                 Ok(mangas) => {
-                    for manga in mangas {
-                        // เช็คว่าเป็น URL ของ sing-manga.com หรือไม่
-                        if manga.url.contains("sing-manga.com") {
-                            // เช็คอัพเดทจากเว็บไซต์
-                            match scrape_manga_sing_manga(&manga.url).await {
-                                Ok((title, latest_chapter, chapter_url, image_url)) => {
-                                    // ถ้าตอนล่าสุดใหม่กว่าในฐานข้อมูล
-                                    if latest_chapter > manga.latest_chapter {
-                                        println!(
-                                            "พบการอัพเดทใหม่สำหรับ {}: ตอนที่ {}",
-                                            title, latest_chapter
-                                        );
-
-                                        // สร้างข้อมูลมังงะใหม่
-                                        let updated_manga = Manga::new(
-                                            title,
-                                            manga.url,
-                                            latest_chapter,
-                                            chapter_url,
-                                            image_url,
-                                        );
-
-                                        // อัพเดทข้อมูลในฐานข้อมูล
-                                        if let Err(e) = MangaService::update(&updated_manga).await {
-                                            println!("เกิดข้อผิดพลาดในการอัพเดทข้อมูลมังงะ: {:?}", e);
-                                            continue;
-                                        }
-
-                                        // ส่งการแจ้งเตือน
-                                        Self::send_update_to_all_channels(ctx, vec![updated_manga])
-                                            .await;
-                                    } else {
-                                        println!(
-                                            "{} ยังไม่มีการอัพเดทใหม่ (ตอนล่าสุด: {})",
-                                            title, manga.latest_chapter
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    println!(
-                                        "เกิดข้อผิดพลาดในการเช็คอัพเดทมังงะ {}: {:?}",
-                                        manga.title, e
-                                    );
+                    let mangas = executor::interleave_by_host(mangas, |manga| {
+                        sources::host_of(&manga.url).unwrap_or_default()
+                    });
+                    stream::iter(mangas)
+                        .for_each_concurrent(executor::MAX_CONCURRENT_SCRAPES, |manga| {
+                            let scrape_run_id = &scrape_run_id;
+                            async move {
+                                if let Some(update) =
+                                    Self::check_manga(ctx, manga, scrape_run_id).await
+                                {
+                                    // ส่งการแจ้งเตือน
+                                    let updates = vec![update];
+                                    Self::send_update_to_subscribers(ctx, &updates).await;
+                                    Self::send_update_to_users(ctx, &updates).await;
                                 }
                             }
-                        }
-                    }
+                        })
+                        .await;
                 }
                 Err(e) => {
-                    println!("เกิดข้อผิดพลาดในการดึงข้อมูลมังงะ: {:?}", e);
+                    println!("เกิดข้อผิดพลาดในการดึงข้อมูลมังงะ: {e:?}");
                 }
             }
         }
     }
-
-    
-    // ฟังก์ชันสำหรับทดสอบการอัพเดท (ทุก 10 วินาที)
-    // pub async fn run_test_update(&self, ctx: &Context) {
-    //     println!("เริ่มการทดสอบการอัพเดท...");
-    //     let mut interval = time::interval(time::Duration::from_secs(10));
-
-    //     // ดึงข้อมูลมังงะทั้งหมดจากฐานข้อมูล
-    //     match MangaService::get_all().await {
-    //         Ok(mangas) => {
-    //             for manga in mangas {
-    //                 if manga.url.contains("sing-manga.com") {
-    //                     match scrape_manga_sing_manga(&manga.url).await {
-    //                         Ok((title, latest_chapter, chapter_url, image_url)) => {
-    //                             // เช็คว่าตอนที่ดึงมาใหม่กว่าในฐานข้อมูลหรือไม่
-    //                             if latest_chapter > manga.latest_chapter {
-    //                                 println!(
-    //                                     "Test Scrape: {} ตอนที่ {} url: {}",
-    //                                     title, latest_chapter, chapter_url
-    //                                 );
-    //                                 println!(
-    //                                     "พบการอัพเดทใหม่! (ตอนเดิม: {}, ตอนใหม่: {})",
-    //                                     manga.latest_chapter, latest_chapter
-    //                                 );
-
-    //                                 // สร้างข้อมูลมังงะใหม่
-    //                                 let updated_manga = Manga::new(
-    //                                     title,
-    //                                     manga.url,
-    //                                     latest_chapter,
-    //                                     chapter_url,
-    //                                     image_url,
-    //                                 );
-
-    //                                 // อัพเดทข้อมูลในฐานข้อมูล
-    //                                 if let Err(e) = MangaService::update(&updated_manga).await {
-    //                                     println!("เกิดข้อผิดพลาดในการอัพเดทข้อมูลมังงะ: {:?}", e);
-    //                                     continue;
-    //                                 }
-
-    //                                 // ส่งแจ้งเตือนไปยังทุกช่อง
-    //                                 Self::send_update_to_all_channels(ctx, vec![updated_manga])
-    //                                     .await;
-    //                             } else {
-    //                                 println!(
-    //                                     "{} ยังไม่มีการอัพเดทใหม่ (ตอนล่าสุด: {})",
-    //                                     title, manga.latest_chapter
-    //                                 );
-    //                             }
-    //                         }
-    //                         Err(e) => {
-    //                             println!("เกิดข้อผิดพลาดในการเช็คอัพเดทมังงะ {}: {:?}", manga.title, e);
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //         Err(e) => {
-    //             println!("เกิดข้อผิดพลาดในการดึงข้อมูลมังงะ: {:?}", e);
-    //         }
-    //     }
-
-    //     // ส่งการอัพเดทอีก 3 ครั้ง (ทดสอบวนซ้ำ)
-    //     for _ in 0..3 {
-    //         interval.tick().await;
-    //         println!("ถึงเวลาอัพเดทแล้ว!");
-
-    //         // ดึงข้อมูลมังงะทั้งหมดอีกครั้ง
-    //         match MangaService::get_all().await {
-    //             Ok(mangas) => {
-    //                 for manga in mangas {
-    //                     if manga.url.contains("sing-manga.com") {
-    //                         match scrape_manga_sing_manga(&manga.url).await {
-    //                             Ok((title, latest_chapter, chapter_url, image_url)) => {
-    //                                 // เช็คว่าตอนที่ดึงมาใหม่กว่าในฐานข้อมูลหรือไม่
-    //                                 if latest_chapter > manga.latest_chapter {
-    //                                     println!(
-    //                                         "Test Scrape: {} ตอนที่ {} url: {}",
-    //                                         title, latest_chapter, chapter_url
-    //                                     );
-    //                                     println!(
-    //                                         "พบการอัพเดทใหม่! (ตอนเดิม: {}, ตอนใหม่: {})",
-    //                                         manga.latest_chapter, latest_chapter
-    //                                     );
-
-    //                                     let updated_manga = Manga::new(
-    //                                         title,
-    //                                         manga.url,
-    //                                         latest_chapter,
-    //                                         chapter_url,
-    //                                         image_url,
-    //                                     );
-
-    //                                     if let Err(e) = MangaService::update(&updated_manga).await {
-    //                                         println!("เกิดข้อผิดพลาดในการอัพเดทข้อมูลมังงะ: {:?}", e);
-    //                                         continue;
-    //                                     }
-
-    //                                     Self::send_update_to_all_channels(ctx, vec![updated_manga])
-    //                                         .await;
-    //                                 } else {
-    //                                     println!(
-    //                                         "{} ยังไม่มีการอัพเดทใหม่ (ตอนล่าสุด: {})",
-    //                                         title, manga.latest_chapter
-    //                                     );
-    //                                 }
-    //                             }
-    //                             Err(e) => {
-    //                                 println!(
-    //                                     "เกิดข้อผิดพลาดในการเช็คอัพเดทมังงะ {}: {:?}",
-    //                                     manga.title, e
-    //                                 );
-    //                             }
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //             Err(e) => {
-    //                 println!("เกิดข้อผิดพลาดในการดึงข้อมูลมังงะ: {:?}", e);
-    //             }
-    //         }
-    //     }
-    // }
 }
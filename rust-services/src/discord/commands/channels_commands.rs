@@ -1,24 +1,111 @@
-use crate::models::channels::Channel;
-use crate::service::channels_service::ChannelsService;
+use super::digest_commands::DigestCommands;
+use super::get_sub_option;
+use crate::discord::delivery;
+use crate::models::channels::{Channel, ChannelLayout, DeliveryMode};
+use crate::service::channels_service::{ChannelUpdate, ChannelsService};
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_thread_service::MangaThreadService;
+use crate::service::pending_notification_service::PendingNotificationService;
 use serenity::all::{
-    Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-    CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    ChannelType, Colour, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EditInteractionResponse, Permissions,
 };
 
 pub fn register() -> CreateCommand {
     CreateCommand::new("channel")
         .description("จัดการช่อง")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
         .add_option(
             CreateCommandOption::new(CommandOptionType::SubCommand, "register", "บันทึกข้อมูลช่อง")
                 .add_sub_option(
                     CreateCommandOption::new(
                         CommandOptionType::Channel,
                         "channel",
-                        "ช่องที่ต้องการบันทึก",
+                        "ช่องที่ต้องการบันทึก (ช่องข้อความ ช่องประกาศ หรือฟอรัม)",
+                    )
+                    .channel_types(vec![
+                        ChannelType::Text,
+                        ChannelType::News,
+                        ChannelType::Forum,
+                    ])
+                    .required(true),
+                )
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "purpose",
+                    "คำอธิบายว่าช่องนี้ใช้ทำอะไร (ใส่ - เพื่อล้างค่า)",
+                ))
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "filter",
+                    "ส่งเฉพาะการ์ตูนที่ชื่อเรื่องมีคำนี้ (ใส่ - เพื่อล้างค่า)",
+                ))
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "threads",
+                    "แยกเธรดของแต่ละเรื่อง (ฟอรัมจะแยกโพสต์ตามเรื่องเสมอ)",
+                ))
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "webhook",
+                    "ส่งผ่าน webhook โดยใช้ชื่อและปกของเรื่องเป็นผู้ส่ง",
+                )),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "unregister", "ลบช่องออกจากระบบ")
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Channel,
+                        "channel",
+                        "ช่องที่ต้องการลบ",
                     )
                     .required(true),
                 ),
         )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "delivery",
+                "ตั้งค่าการส่งแจ้งเตือนทันทีหรือเป็นสรุปตามรอบ",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Channel, "channel", "ช่องที่ต้องการตั้งค่า")
+                    .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "mode", "วิธีส่งแจ้งเตือน")
+                    .required(true)
+                    .add_string_choice("ส่งทันที", "immediate")
+                    .add_string_choice("สรุปทุกชั่วโมง", "hourly")
+                    .add_string_choice("สรุปทุกวัน", "daily")
+                    .add_string_choice("สรุปทุกสัปดาห์", "weekly"),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "hour",
+                    "ชั่วโมงที่ส่งสรุป (0-23 ตามเขตเวลาของเซิร์ฟเวอร์) ใช้กับสรุปทุกวันและทุกสัปดาห์",
+                )
+                .min_int_value(0)
+                .max_int_value(23),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "weekday",
+                    "วันที่ส่งสรุป ใช้กับสรุปทุกสัปดาห์",
+                )
+                .add_int_choice("วันจันทร์", 0)
+                .add_int_choice("วันอังคาร", 1)
+                .add_int_choice("วันพุธ", 2)
+                .add_int_choice("วันพฤหัสบดี", 3)
+                .add_int_choice("วันศุกร์", 4)
+                .add_int_choice("วันเสาร์", 5)
+                .add_int_choice("วันอาทิตย์", 6),
+            ),
+        )
         .add_option(CreateCommandOption::new(
             CommandOptionType::SubCommand,
             "list",
@@ -52,6 +139,26 @@ pub async fn show_channel_info_ui(
         .await
 }
 
+// UI Utility Function สำหรับคำสั่งที่ defer ไว้แล้ว
+async fn edit_channel_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบจัดการช่อง"));
+
+    command
+        .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
 async fn list_channels(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
     let guild_id = command.guild_id.unwrap();
 
@@ -70,11 +177,23 @@ async fn list_channels(ctx: &Context, command: &CommandInteraction) -> serenity:
                 let description = channels
                     .iter()
                     .map(|channel| {
-                        format!(
-                            "**{}** ({})\n\
-                        ",
-                            channel.channel_name, channel.channel_id,
-                        )
+                        let mut line =
+                            format!("**{}** ({})", channel.channel_name, channel.channel_id);
+                        if let Some(purpose) = &channel.purpose {
+                            line.push_str(&format!("\nหน้าที่: {purpose}"));
+                        }
+                        if let Some(filter) = &channel.title_filter {
+                            line.push_str(&format!("\nตัวกรอง: {filter}"));
+                        }
+                        line.push_str(&format!(
+                            "\nการส่ง: {} • {}",
+                            channel.delivery_mode.describe(),
+                            channel.layout.describe()
+                        ));
+                        if channel.webhook.is_some() {
+                            line.push_str(" • ผ่าน webhook");
+                        }
+                        line
                     })
                     .collect::<Vec<String>>()
                     .join("\n");
@@ -88,7 +207,7 @@ async fn list_channels(ctx: &Context, command: &CommandInteraction) -> serenity:
                 command,
                 ctx,
                 "เกิดข้อผิดพลาด",
-                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {}", e),
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
                 Colour::RED,
             )
             .await
@@ -110,6 +229,9 @@ async fn add_channel(ctx: &Context, command: &CommandInteraction) -> serenity::R
         .and_then(|opt| opt.value.as_channel_id())
         .unwrap();
 
+    // การดึงข้อมูลช่องและการสร้าง webhook อาจเกินเวลาตอบกลับ 3 วินาที จึง defer ไว้ก่อน
+    command.defer_ephemeral(&ctx.http).await?;
+
     let guild_id = command.guild_id.unwrap();
     let guild = ctx.http.get_guild(guild_id).await?;
     let guild_name: String = guild.name;
@@ -117,47 +239,91 @@ async fn add_channel(ctx: &Context, command: &CommandInteraction) -> serenity::R
     match channel_id.to_channel(&ctx.http).await {
         Ok(channel) => {
             if let serenity::model::channel::Channel::Guild(channel) = channel {
+                // None = ไม่ได้ระบุ option threads ช่องที่ลงทะเบียนไว้แล้วจะคงรูปแบบเดิม
+                let layout = match channel.kind {
+                    ChannelType::Forum => Some(ChannelLayout::Forum),
+                    ChannelType::Text | ChannelType::News => {
+                        get_sub_option(command, "register", "threads")
+                            .and_then(|value| value.as_bool())
+                            .map(|threads| {
+                                if threads {
+                                    ChannelLayout::Thread
+                                } else {
+                                    ChannelLayout::Channel
+                                }
+                            })
+                    }
+                    _ => {
+                        return edit_channel_info_ui(
+                            command,
+                            ctx,
+                            "ไม่รองรับช่องนี้",
+                            "รองรับเฉพาะช่องข้อความ ช่องประกาศ และฟอรัม",
+                            Colour::RED,
+                        )
+                        .await;
+                    }
+                };
+
                 let existing_channels: Vec<Channel> = match ChannelsService::get_channels_by_guild(&guild_id.to_string()).await {
                     Ok(channels) => channels,
                     Err(e) => {
-                        return show_channel_info_ui(
+                        return edit_channel_info_ui(
                             command,
                             ctx,
                             "เกิดข้อผิดพลาด",
-                            &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {}", e),
+                            &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
                             Colour::RED,
                         )
                         .await;
                     }
                 };
-                println!("existing_channels: {:?}", existing_channels);
+                println!("existing_channels: {existing_channels:?}");
                 
-                let channel_doc: Channel = Channel::new(
-                    channel.id.to_string(),
-                    guild_id.to_string(),
-                    channel.name.clone(),
-                    guild_name.clone(),
-                );
-
-                if existing_channels.iter().any(|c: &Channel| c.guild_id == guild_id.to_string()) {
+                let changes = ChannelUpdate {
+                    purpose: get_string_option(command, "purpose"),
+                    title_filter: get_string_option(command, "filter"),
+                    layout,
+                };
+                let channel_doc: Channel = Channel {
+                    purpose: changes.purpose.clone().flatten(),
+                    title_filter: changes.title_filter.clone().flatten(),
+                    layout: changes.layout.unwrap_or_default(),
+                    ..Channel::new(
+                        channel.id.to_string(),
+                        guild_id.to_string(),
+                        channel.name.clone(),
+                        guild_name.clone(),
+                    )
+                };
+
+                if existing_channels
+                    .iter()
+                    .any(|c: &Channel| c.channel_id == channel.id.to_string())
+                {
                     // Update existing channel
-                    match ChannelsService::update_channel(&channel_doc).await {
+                    match ChannelsService::update_channel(&channel_doc, &changes).await {
                         Ok(_) => {
-                            show_channel_info_ui(
+                            let webhook_note =
+                                apply_webhook_option(ctx, command, &channel_doc).await;
+                            edit_channel_info_ui(
                                 command,
                                 ctx,
                                 "อัพเดทข้อมูลสำเร็จ",
-                                &format!("อัพเดทข้อมูลช่อง {} ลงฐานข้อมูลสำเร็จ", channel.name),
+                                &format!(
+                                    "อัพเดทข้อมูลช่อง {} ลงฐานข้อมูลสำเร็จ{webhook_note}",
+                                    channel.name
+                                ),
                                 Colour::DARK_GREEN,
                             )
                             .await
                         }
                         Err(e) => {
-                            show_channel_info_ui(
+                            edit_channel_info_ui(
                                 command,
                                 ctx,
                                 "เกิดข้อผิดพลาด",
-                                &format!("เกิดข้อผิดพลาดในการอัพเดทข้อมูล: {}", e),
+                                &format!("เกิดข้อผิดพลาดในการอัพเดทข้อมูล: {e}"),
                                 Colour::RED,
                             )
                             .await
@@ -165,23 +331,28 @@ async fn add_channel(ctx: &Context, command: &CommandInteraction) -> serenity::R
                     }
                 } else {
                     // Create new channel
-                    match ChannelsService::create_channel(channel_doc).await {
+                    match ChannelsService::create_channel(channel_doc.clone()).await {
                         Ok(_) => {
-                            show_channel_info_ui(
+                            let webhook_note =
+                                apply_webhook_option(ctx, command, &channel_doc).await;
+                            edit_channel_info_ui(
                                 command,
                                 ctx,
                                 "เพิ่มข้อมูลสำเร็จ",
-                                &format!("เพิ่มข้อมูลช่อง {} ลงฐานข้อมูลสำเร็จ", channel.name),
+                                &format!(
+                                    "เพิ่มข้อมูลช่อง {} ลงฐานข้อมูลสำเร็จ{webhook_note}",
+                                    channel.name
+                                ),
                                 Colour::DARK_GREEN,
                             )
                             .await
                         }
                         Err(e) => {
-                            show_channel_info_ui(
+                            edit_channel_info_ui(
                                 command,
                                 ctx,
                                 "เกิดข้อผิดพลาด",
-                                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {}", e),
+                                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
                                 Colour::RED,
                             )
                             .await
@@ -189,7 +360,7 @@ async fn add_channel(ctx: &Context, command: &CommandInteraction) -> serenity::R
                     }
                 }
             } else {
-                show_channel_info_ui(
+                edit_channel_info_ui(
                     command,
                     ctx,
                     "เกิดข้อผิดพลาด",
@@ -199,12 +370,220 @@ async fn add_channel(ctx: &Context, command: &CommandInteraction) -> serenity::R
                 .await
             }
         }
+        Err(e) => {
+            edit_channel_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("ไม่พบช่องที่ระบุ: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+// เปิดหรือปิดการส่งผ่าน webhook ตาม option webhook ถ้าไม่ระบุจะคงค่าเดิม
+// คืนข้อความที่ต่อท้ายผลการบันทึกช่อง
+async fn apply_webhook_option(
+    ctx: &Context,
+    command: &CommandInteraction,
+    channel: &Channel,
+) -> String {
+    let Some(enabled) =
+        get_sub_option(command, "register", "webhook").and_then(|value| value.as_bool())
+    else {
+        return String::new();
+    };
+    let saved = match ChannelsService::get_channel(&channel.guild_id, &channel.channel_id).await {
+        Ok(Some(saved)) => saved,
+        Ok(None) => return "\nไม่พบข้อมูลช่อง จึงไม่ได้ตั้งค่า webhook".to_string(),
+        Err(e) => return format!("\nเกิดข้อผิดพลาดในการตั้งค่า webhook: {e}"),
+    };
+
+    match (enabled, &saved.webhook) {
+        (true, Some(_)) => "\nส่งผ่าน webhook อยู่แล้ว".to_string(),
+        (true, None) => match delivery::create_webhook(ctx, &saved).await {
+            Ok(_) => "\nสร้าง webhook สำหรับส่งการแจ้งเตือนแล้ว".to_string(),
+            Err(e @ serenity::Error::Http(_)) => {
+                format!("\nสร้าง webhook ไม่สำเร็จ บอทต้องมีสิทธิ์จัดการ webhook ในช่องนี้: {e}")
+            }
+            Err(e) => format!("\nสร้าง webhook ไม่สำเร็จ: {e}"),
+        },
+        (false, Some(webhook)) => {
+            if let Err(e) = delivery::delete_webhook(ctx, webhook).await {
+                println!("เกิดข้อผิดพลาดในการลบ webhook: {e:?}");
+            }
+            match ChannelsService::set_webhook(&saved.guild_id, &saved.channel_id, None).await {
+                Ok(()) => "\nเลิกส่งผ่าน webhook แล้ว".to_string(),
+                Err(e) => format!("\nเกิดข้อผิดพลาดในการเลิกใช้ webhook: {e}"),
+            }
+        }
+        (false, None) => String::new(),
+    }
+}
+
+// ค่า option แบบข้อความของคำสั่ง register None = ไม่ได้ระบุ
+// Some(None) = ล้างค่า (ระบุเป็น - หรือข้อความว่าง)
+fn get_string_option(command: &CommandInteraction, name: &str) -> Option<Option<String>> {
+    get_sub_option(command, "register", name)
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .map(|value| Some(value).filter(|value| !value.is_empty() && value != "-"))
+}
+
+async fn remove_channel(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let Some(channel_id) =
+        get_sub_option(command, "unregister", "channel").and_then(|value| value.as_channel_id())
+    else {
+        return show_channel_info_ui(command, ctx, "เกิดข้อผิดพลาด", "กรุณาระบุช่อง", Colour::RED)
+            .await;
+    };
+    let guild_id = command.guild_id.unwrap();
+
+    // ลบ webhook ที่บอทสร้างไว้ในช่องนี้ก่อนลบข้อมูลช่อง
+    if let Ok(Some(Channel {
+        webhook: Some(webhook),
+        ..
+    })) = ChannelsService::get_channel(&guild_id.to_string(), &channel_id.to_string()).await
+    {
+        if let Err(e) = delivery::delete_webhook(ctx, &webhook).await {
+            println!("เกิดข้อผิดพลาดในการลบ webhook ของช่อง: {e:?}");
+        }
+    }
+
+    match ChannelsService::delete_channel(&guild_id.to_string(), &channel_id.to_string()).await {
+        Ok(true) => {
+            if let Err(e) = GuildSubscriptionService::delete_by_channel(
+                &guild_id.to_string(),
+                &channel_id.to_string(),
+            )
+            .await
+            {
+                println!("เกิดข้อผิดพลาดในการลบการติดตามของช่อง: {e:?}");
+            }
+            if let Err(e) = PendingNotificationService::delete_by_channel(
+                &guild_id.to_string(),
+                &channel_id.to_string(),
+            )
+            .await
+            {
+                println!("เกิดข้อผิดพลาดในการลบตอนที่รอส่งของช่อง: {e:?}");
+            }
+            if let Err(e) = MangaThreadService::delete_by_channel(
+                &guild_id.to_string(),
+                &channel_id.to_string(),
+            )
+            .await
+            {
+                println!("เกิดข้อผิดพลาดในการลบเธรดของช่อง: {e:?}");
+            }
+            show_channel_info_ui(
+                command,
+                ctx,
+                "ลบข้อมูลสำเร็จ",
+                &format!("ลบช่อง <#{channel_id}> ออกจากระบบแล้ว"),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Ok(false) => {
+            show_channel_info_ui(
+                command,
+                ctx,
+                "ไม่พบช่อง",
+                &format!("ช่อง <#{channel_id}> ไม่ได้บันทึกไว้ในระบบ"),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Err(e) => {
+            show_channel_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการลบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn set_delivery(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap();
+    let Some(channel_id) =
+        get_sub_option(command, "delivery", "channel").and_then(|value| value.as_channel_id())
+    else {
+        return show_channel_info_ui(command, ctx, "เกิดข้อผิดพลาด", "กรุณาระบุช่อง", Colour::RED).await;
+    };
+    let mode = get_sub_option(command, "delivery", "mode")
+        .and_then(|value| value.as_str())
+        .unwrap_or("immediate");
+    let hour = get_sub_option(command, "delivery", "hour")
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0) as u32;
+    let weekday = get_sub_option(command, "delivery", "weekday")
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0) as u32;
+
+    let delivery_mode = match mode {
+        "hourly" => DeliveryMode::Hourly,
+        "daily" => DeliveryMode::Daily { hour },
+        "weekly" => DeliveryMode::Weekly { weekday, hour },
+        _ => DeliveryMode::Immediate,
+    };
+
+    match ChannelsService::set_delivery_mode(
+        &guild_id.to_string(),
+        &channel_id.to_string(),
+        &delivery_mode,
+    )
+    .await
+    {
+        Ok(true) => {
+            // เปลี่ยนกลับเป็นส่งทันที ส่งตอนที่ยังค้างในคิวออกไปเป็นสรุปสุดท้าย
+            if delivery_mode == DeliveryMode::Immediate {
+                let channels = ChannelsService::get_channels_by_guild(&guild_id.to_string())
+                    .await
+                    .unwrap_or_default();
+                if let Some(channel) = channels
+                    .into_iter()
+                    .find(|channel| channel.channel_id == channel_id.to_string())
+                {
+                    let ctx = ctx.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = DigestCommands::send_digest(&ctx, &channel).await {
+                            println!("เกิดข้อผิดพลาดในการส่งตอนที่ค้างอยู่: {e}");
+                        }
+                    });
+                }
+            }
+            show_channel_info_ui(
+                command,
+                ctx,
+                "ตั้งค่าการส่งสำเร็จ",
+                &format!("ช่อง <#{channel_id}>: {}", delivery_mode.describe()),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Ok(false) => {
+            show_channel_info_ui(
+                command,
+                ctx,
+                "ไม่พบช่อง",
+                &format!("ช่อง <#{channel_id}> ไม่ได้บันทึกไว้ในระบบ ใช้ /channel register ก่อน"),
+                Colour::GOLD,
+            )
+            .await
+        }
         Err(e) => {
             show_channel_info_ui(
                 command,
                 ctx,
                 "เกิดข้อผิดพลาด",
-                &format!("ไม่พบช่องที่ระบุ: {}", e),
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
                 Colour::RED,
             )
             .await
@@ -222,6 +601,8 @@ pub async fn run(
 
     match subcommand_name.as_str() {
         "register" => add_channel(ctx, command).await,
+        "unregister" => remove_channel(ctx, command).await,
+        "delivery" => set_delivery(ctx, command).await,
         "list" => list_channels(ctx, command).await,
         _ => show_channel_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
     }
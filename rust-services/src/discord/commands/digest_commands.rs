@@ -0,0 +1,276 @@
+use crate::discord::delivery::{self, Branding, OutgoingMessage};
+use crate::models::channels::{Channel, DeliveryMode};
+use crate::models::guild_settings::GuildSettings;
+use crate::models::pending_notification::PendingNotification;
+use crate::service::channels_service::ChannelsService;
+use crate::service::guild_settings_service::GuildSettingsService;
+use crate::service::manga_role_service::MangaRoleService;
+use crate::service::pending_notification_service::PendingNotificationService;
+use mongodb::bson::oid::ObjectId;
+use serenity::all::{
+    Colour, Context, CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, RoleId,
+};
+use tokio::time;
+
+// จำนวนตอนของแต่ละเรื่องที่แสดงลิงก์ครบ ถ้ามากกว่านี้จะแสดงเป็นช่วงตอน
+const MAX_LISTED_CHAPTERS: usize = 5;
+// ความยาวสูงสุดของคำอธิบายในสรุป (Discord จำกัด 4096 ตัวอักษร)
+const MAX_DIGEST_LENGTH: usize = 3800;
+// ชื่อผู้ส่งของสรุปรวมเมื่อส่งผ่าน webhook
+const DIGEST_USERNAME: &str = "สรุปตอนใหม่";
+
+// โครงสร้างสำหรับส่งสรุปตอนใหม่ของช่องที่ใช้โหมดสรุปตามรอบ
+pub struct DigestCommands;
+
+impl DigestCommands {
+    pub fn new() -> Self {
+        DigestCommands
+    }
+
+    // จัดกลุ่มตอนที่รอส่งตามมังงะ (เรียงตามลำดับที่พบ)
+    fn group_by_manga(
+        notifications: &[PendingNotification],
+    ) -> Vec<(ObjectId, Vec<&PendingNotification>)> {
+        let mut groups: Vec<(ObjectId, Vec<&PendingNotification>)> = Vec::new();
+        for notification in notifications {
+            match groups
+                .iter_mut()
+                .find(|(manga_id, _)| *manga_id == notification.manga_id)
+            {
+                Some((_, chapters)) => chapters.push(notification),
+                None => groups.push((notification.manga_id, vec![notification])),
+            }
+        }
+        for (_, chapters) in groups.iter_mut() {
+            chapters.sort_by(|a, b| a.chapter_number.cmp(&b.chapter_number));
+        }
+        groups
+    }
+
+    fn build_digest_message(
+        channel: &Channel,
+        notifications: &[PendingNotification],
+        role_ids: Vec<RoleId>,
+    ) -> OutgoingMessage {
+        let groups = Self::group_by_manga(notifications);
+
+        let mut description = String::new();
+        for (index, (_, chapters)) in groups.iter().enumerate() {
+            let (first, last) = (chapters[0], chapters[chapters.len() - 1]);
+            let line = if chapters.len() <= MAX_LISTED_CHAPTERS {
+                format!(
+                    "**{}**\n{}",
+                    first.manga_title,
+                    chapters
+                        .iter()
+                        .map(|c| format!("[ตอนที่ {}]({})", c.chapter_number, c.chapter_url))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            } else {
+                format!(
+                    "**{}**\nตอนที่ {} - {} ({} ตอน) ล่าสุด: [ตอนที่ {}]({})",
+                    first.manga_title,
+                    first.chapter_number,
+                    last.chapter_number,
+                    chapters.len(),
+                    last.chapter_number,
+                    last.chapter_url
+                )
+            };
+
+            if description.len() + line.len() > MAX_DIGEST_LENGTH {
+                description.push_str(&format!("\n\nและอีก {} เรื่อง", groups.len() - index));
+                break;
+            }
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            description.push_str(&line);
+        }
+
+        let embed = CreateEmbed::new()
+            .title(format!(
+                "สรุปตอนใหม่: {} ตอนจาก {} เรื่อง",
+                notifications.len(),
+                groups.len()
+            ))
+            .description(description)
+            .color(Colour::DARK_GREEN)
+            .footer(CreateEmbedFooter::new(match channel.delivery_mode {
+                DeliveryMode::Immediate => {
+                    "ระบบอัพเดทมังงะอัตโนมัติ • ตอนที่พักไว้ระหว่างช่วงงดแจ้งเตือน".to_string()
+                }
+                _ => format!("ระบบอัพเดทมังงะอัตโนมัติ • {}", channel.delivery_mode.describe()),
+            }));
+
+        let message = OutgoingMessage::new(embed);
+        if role_ids.is_empty() {
+            return message.allowed_mentions(CreateAllowedMentions::new());
+        }
+        message
+            .content(
+                role_ids
+                    .iter()
+                    .map(|role_id| format!("<@&{role_id}>"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            )
+            .allowed_mentions(CreateAllowedMentions::new().roles(role_ids))
+    }
+
+    // ส่งสรุปของช่องหนึ่งช่อง แล้วลบตอนที่ส่งแล้วออกจากคิว
+    pub async fn send_digest(ctx: &Context, channel: &Channel) -> Result<(), String> {
+        let notifications =
+            PendingNotificationService::get_by_channel(&channel.guild_id, &channel.channel_id)
+                .await
+                .map_err(|e| format!("ดึงตอนที่รอส่งไม่สำเร็จ: {e:?}"))?;
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        // mention role ของมังงะที่อยู่ในสรุปนี้
+        let roles = MangaRoleService::get_by_guild(&channel.guild_id)
+            .await
+            .unwrap_or_default();
+        let role_ids_for = |notifications: &[PendingNotification]| -> Vec<RoleId> {
+            roles
+                .iter()
+                .filter(|role| notifications.iter().any(|n| n.manga_id == role.manga_id))
+                .filter_map(|role| role.role_id.parse::<u64>().ok().map(RoleId::new))
+                .collect()
+        };
+
+        if !channel.layout.per_manga() {
+            let message =
+                Self::build_digest_message(channel, &notifications, role_ids_for(&notifications));
+            let branding = Branding {
+                username: DIGEST_USERNAME.to_string(),
+                avatar_url: None,
+            };
+            delivery::send_channel_message(ctx, channel, &message, &branding)
+                .await
+                .map_err(|e| format!("ส่งข้อความไม่สำเร็จ: {e:?}"))?;
+
+            let ids: Vec<ObjectId> = notifications.iter().filter_map(|n| n.id).collect();
+            PendingNotificationService::delete_by_ids(&ids)
+                .await
+                .map_err(|e| format!("ลบตอนที่ส่งแล้วออกจากคิวไม่สำเร็จ: {e:?}"))?;
+            return Ok(());
+        }
+
+        // ช่องที่แยกเธรดตามเรื่องจะส่งสรุปของแต่ละเรื่องเข้าเธรดของเรื่องนั้น
+        // เรื่องที่ส่งไม่สำเร็จจะยังอยู่ในคิวรอส่งรอบถัดไป
+        let mut sent_ids: Vec<ObjectId> = Vec::new();
+        let mut failed = 0;
+        for (manga_id, chapters) in Self::group_by_manga(&notifications) {
+            let group: Vec<PendingNotification> = chapters.into_iter().cloned().collect();
+            let message = Self::build_digest_message(channel, &group, role_ids_for(&group));
+            match delivery::send_manga_message(
+                ctx,
+                channel,
+                &manga_id,
+                &group[0].manga_title,
+                &message,
+                &Branding {
+                    username: group[0].manga_title.clone(),
+                    avatar_url: None,
+                },
+            )
+            .await
+            {
+                Ok(()) => sent_ids.extend(group.iter().filter_map(|n| n.id)),
+                Err(e) => {
+                    println!("เกิดข้อผิดพลาดในการส่งสรุปของ {}: {e:?}", group[0].manga_title);
+                    failed += 1;
+                }
+            }
+        }
+
+        PendingNotificationService::delete_by_ids(&sent_ids)
+            .await
+            .map_err(|e| format!("ลบตอนที่ส่งแล้วออกจากคิวไม่สำเร็จ: {e:?}"))?;
+        if failed > 0 {
+            return Err(format!("ส่งสรุปไม่สำเร็จ {failed} เรื่อง"));
+        }
+        Ok(())
+    }
+
+    // ตรวจทุกนาทีว่าช่องใดถึงรอบส่งสรุปแล้ว และส่งตอนที่พักไว้ระหว่างช่วงงดแจ้งเตือน
+    pub async fn run_digest_scheduler(&self, ctx: &Context) {
+        println!("เริ่มการทำงานส่งสรุปตามรอบ...");
+        let mut interval = time::interval(time::Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            let channels = match ChannelsService::get_all_channels().await {
+                Ok(channels) => channels,
+                Err(e) => {
+                    println!("เกิดข้อผิดพลาดในการดึง channels: {e:?}");
+                    continue;
+                }
+            };
+
+            let settings = match GuildSettingsService::get_all().await {
+                Ok(settings) => settings,
+                Err(e) => {
+                    println!("เกิดข้อผิดพลาดในการดึงการตั้งค่าเซิร์ฟเวอร์: {e:?}");
+                    continue;
+                }
+            };
+
+            let now = chrono::Utc::now();
+            for channel in channels {
+                let settings = settings
+                    .get(&channel.guild_id)
+                    .cloned()
+                    .unwrap_or_else(|| GuildSettings::new(channel.guild_id.clone()));
+
+                // ระหว่างช่วงงดแจ้งเตือนจะพักไว้ก่อน รอบที่ถึงกำหนดระหว่างนี้จะส่งเมื่อพ้นช่วงแล้ว
+                if settings.is_quiet_at(now) {
+                    continue;
+                }
+
+                // ช่องที่ส่งทันทีอาจมีตอนที่พักไว้ระหว่างช่วงงดแจ้งเตือน ส่งออกไปทันทีที่พ้นช่วง
+                if channel.delivery_mode == DeliveryMode::Immediate {
+                    if let Err(e) = Self::send_digest(ctx, &channel).await {
+                        println!(
+                            "เกิดข้อผิดพลาดในการส่งตอนที่พักไว้ไปยังช่อง {}: {e}",
+                            channel.channel_id
+                        );
+                    }
+                    continue;
+                }
+
+                let Some(slot) = channel
+                    .delivery_mode
+                    .last_slot(now.with_timezone(&settings.tz()))
+                else {
+                    continue;
+                };
+                let last_digest_at = channel
+                    .last_digest_at
+                    .map(|at| chrono::DateTime::<chrono::Utc>::from(at.to_system_time()));
+                if last_digest_at.is_some_and(|at| at >= slot) {
+                    continue;
+                }
+
+                if let Err(e) = Self::send_digest(ctx, &channel).await {
+                    println!("เกิดข้อผิดพลาดในการส่งสรุปไปยังช่อง {}: {e}", channel.channel_id);
+                }
+
+                // นับรอบนี้ว่าส่งแล้วแม้ส่งไม่สำเร็จ ตอนที่ค้างอยู่จะถูกส่งในรอบถัดไป
+                if let Err(e) = ChannelsService::mark_digest_sent(
+                    &channel.guild_id,
+                    &channel.channel_id,
+                    mongodb::bson::DateTime::from(std::time::SystemTime::from(now)),
+                )
+                .await
+                {
+                    println!("เกิดข้อผิดพลาดในการบันทึกเวลาส่งสรุป: {e:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use super::get_sub_option;
+use super::manga_commands::{find_manga, MANGA_NOT_FOUND};
+use crate::models::manga::Manga;
+use crate::models::user_subscription::UserSubscription;
+use crate::service::user_subscription_service::UserSubscriptionService;
+use serenity::all::{
+    Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+    CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, HttpError, UserId,
+};
+
+// รหัสข้อผิดพลาดของ Discord เมื่อส่ง DM หาผู้ใช้ที่ปิดรับ DM หรือไม่มีเซิร์ฟเวอร์ร่วมกัน
+const CANNOT_SEND_DM_ERROR_CODE: isize = 50007;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("dm")
+        .description("รับแจ้งเตือนตอนใหม่ทางข้อความส่วนตัว")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "subscribe",
+                "รับแจ้งเตือนการ์ตูนเรื่องนี้ทาง DM",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "unsubscribe",
+                "เลิกรับแจ้งเตือนการ์ตูนเรื่องนี้ทาง DM",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "ดูรายการการ์ตูนที่รับแจ้งเตือนทาง DM",
+        ))
+}
+
+// ตรวจว่าข้อผิดพลาดเกิดจากผู้ใช้ปิดรับ DM หรือไม่
+pub fn is_dm_closed_error(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == CANNOT_SEND_DM_ERROR_CODE
+    )
+}
+
+// UI Utility Function
+pub async fn show_dm_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบแจ้งเตือนทาง DM"));
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// ดึงการ์ตูนจาก option manga ของคำสั่งย่อย ถ้าไม่พบจะตอบกลับผู้ใช้และคืน None
+async fn get_manga_option(
+    ctx: &Context,
+    command: &CommandInteraction,
+    subcommand: &str,
+) -> serenity::Result<Option<Manga>> {
+    let query = get_sub_option(command, subcommand, "manga")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    match find_manga(query).await {
+        Ok(Some(manga)) => Ok(Some(manga)),
+        Ok(None) => {
+            show_dm_info_ui(command, ctx, "ไม่พบข้อมูล", MANGA_NOT_FOUND, Colour::RED).await?;
+            Ok(None)
+        }
+        Err(e) => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+// ส่ง DM ยืนยันการติดตาม เพื่อตรวจว่าผู้ใช้เปิดรับ DM อยู่หรือไม่
+async fn send_confirmation_dm(
+    ctx: &Context,
+    user_id: UserId,
+    manga: &Manga,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title("เริ่มรับแจ้งเตือนทาง DM")
+        .description(format!(
+            "จะแจ้งเตือนเมื่อ **{}** มีตอนใหม่\nตอนล่าสุดตอนนี้: [ตอนที่ {}]({})",
+            manga.title, manga.latest_chapter, manga.latest_chapter_url
+        ))
+        .color(Colour::DARK_GREEN)
+        .footer(CreateEmbedFooter::new("ระบบแจ้งเตือนทาง DM"));
+
+    user_id
+        .direct_message(&ctx.http, CreateMessage::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+async fn subscribe_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let Some(manga) = get_manga_option(ctx, command, "subscribe").await? else {
+        return Ok(());
+    };
+    let user_id = command.user.id.to_string();
+
+    let subscription =
+        UserSubscription::new(user_id.clone(), manga.id.unwrap(), manga.title.clone());
+    let created = match UserSubscriptionService::subscribe(&subscription).await {
+        Ok(created) => created,
+        Err(e) => {
+            return show_dm_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+
+    match send_confirmation_dm(ctx, command.user.id, &manga).await {
+        Ok(_) => {
+            // ส่ง DM ได้แล้ว การติดตามที่เคยหยุดไว้จะกลับมาส่งตามปกติ
+            if let Err(e) = UserSubscriptionService::mark_dm_open(&user_id).await {
+                println!("เกิดข้อผิดพลาดในการเปิดการแจ้งเตือนทาง DM: {e:?}");
+            }
+            let description = if created {
+                format!("จะส่งตอนใหม่ของ **{}** ทาง DM", manga.title)
+            } else {
+                format!("รับแจ้งเตือน **{}** ทาง DM อยู่แล้ว", manga.title)
+            };
+            show_dm_info_ui(
+                command,
+                ctx,
+                "ติดตามการ์ตูนสำเร็จ",
+                &description,
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) if is_dm_closed_error(&e) => {
+            if let Err(e) = UserSubscriptionService::mark_dm_closed(&user_id).await {
+                println!("เกิดข้อผิดพลาดในการหยุดการแจ้งเตือนทาง DM: {e:?}");
+            }
+            show_dm_info_ui(
+                command,
+                ctx,
+                "ไม่สามารถส่ง DM ได้",
+                &format!(
+                    "บันทึกการติดตาม **{}** แล้ว แต่บอทส่ง DM หาคุณไม่ได้\n\
+                    กรุณาเปิด \"อนุญาตข้อความส่วนตัวจากสมาชิกเซิร์ฟเวอร์\" แล้วใช้คำสั่งนี้อีกครั้ง",
+                    manga.title
+                ),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการส่ง DM ยืนยัน: {e:?}");
+            show_dm_info_ui(
+                command,
+                ctx,
+                "ติดตามการ์ตูนสำเร็จ",
+                &format!("จะส่งตอนใหม่ของ **{}** ทาง DM", manga.title),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+    }
+}
+
+async fn unsubscribe_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let Some(manga) = get_manga_option(ctx, command, "unsubscribe").await? else {
+        return Ok(());
+    };
+
+    match UserSubscriptionService::unsubscribe(&command.user.id.to_string(), &manga.id.unwrap())
+        .await
+    {
+        Ok(true) => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "เลิกติดตามสำเร็จ",
+                &format!("เลิกส่งแจ้งเตือน **{}** ทาง DM แล้ว", manga.title),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Ok(false) => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "ไม่ได้ติดตาม",
+                &format!("คุณไม่ได้รับแจ้งเตือน **{}** ทาง DM", manga.title),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Err(e) => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการลบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn list_subscriptions(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    match UserSubscriptionService::get_by_user(&command.user.id.to_string()).await {
+        Ok(subscriptions) if subscriptions.is_empty() => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "การ์ตูนที่ติดตาม",
+                "คุณยังไม่ได้รับแจ้งเตือนการ์ตูนเรื่องใดทาง DM ใช้ /dm subscribe เพื่อเริ่มติดตาม",
+                Colour::GOLD,
+            )
+            .await
+        }
+        Ok(subscriptions) => {
+            let mut description = subscriptions
+                .iter()
+                .map(|subscription| format!("• **{}**", subscription.manga_title))
+                .collect::<Vec<String>>()
+                .join("\n");
+            if subscriptions.iter().any(|s| s.dm_closed_at.is_some()) {
+                description.push_str(
+                    "\n\n⚠️ หยุดส่งแจ้งเตือนชั่วคราวเพราะบอทส่ง DM หาคุณไม่ได้ \
+                    เปิดรับ DM แล้วใช้ /dm subscribe อีกครั้งเพื่อกลับมารับแจ้งเตือน",
+                );
+            }
+
+            show_dm_info_ui(command, ctx, "การ์ตูนที่ติดตาม", &description, Colour::BLUE).await
+        }
+        Err(e) => {
+            show_dm_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    _: &serenity::prelude::TypeMap,
+) -> serenity::Result<()> {
+    let subcommand = command.data.options.first().unwrap();
+    let subcommand_name = &subcommand.name;
+
+    match subcommand_name.as_str() {
+        "subscribe" => subscribe_manga(ctx, command).await,
+        "unsubscribe" => unsubscribe_manga(ctx, command).await,
+        "list" => list_subscriptions(ctx, command).await,
+        _ => show_dm_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
+    }
+}
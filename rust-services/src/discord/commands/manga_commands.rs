@@ -1,10 +1,38 @@
+use super::get_sub_option;
+use crate::models::chapter_number::ChapterNumber;
+use crate::models::guild_settings::GuildSettings;
+use crate::models::guild_subscription::GuildSubscription;
 use crate::models::manga::Manga;
+use crate::models::release_pattern::ReleasePattern;
+use crate::service::chapter_service::ChapterService;
+use crate::service::guild_settings_service::GuildSettingsService;
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_role_service::MangaRoleService;
 use crate::service::manga_service::MangaService;
+use crate::service::manga_thread_service::MangaThreadService;
+use crate::service::pending_notification_service::PendingNotificationService;
+use crate::service::reading_progress_service::ReadingProgressService;
+use crate::service::user_subscription_service::UserSubscriptionService;
+use crate::sources::{self, ScrapeResult};
+use crate::utils::mongo;
+use crate::utils::time::discord_timestamp;
+use mongodb::bson::oid::ObjectId;
 use serenity::all::{
-    Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+    ButtonStyle, Colour, CommandInteraction, CommandOptionType, ComponentInteraction, Context,
+    CreateActionRow, CreateAutocompleteResponse, CreateButton, CreateCommand, CreateCommandOption,
     CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    EditInteractionResponse,
 };
 
+// จำนวนการ์ตูนต่อหน้าของคำสั่ง list
+const PAGE_SIZE: u64 = 10;
+// จำนวนตัวเลือกสูงสุดที่ Discord รับได้ใน autocomplete
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+// ความยาวสูงสุดของชื่อตัวเลือกใน autocomplete
+const MAX_CHOICE_NAME_LENGTH: usize = 100;
+// จำนวนการ์ตูนสูงสุดที่แสดงในคำสั่ง unread
+const MAX_UNREAD_LISTED: usize = 20;
+
 pub fn register() -> CreateCommand {
     CreateCommand::new("manga")
         .description("จัดการการ์ตูน")
@@ -19,6 +47,99 @@ pub fn register() -> CreateCommand {
                     .required(true),
                 ),
         )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "list", "ดูรายการการ์ตูนทั้งหมด")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "page", "หน้าที่ต้องการดู")
+                        .min_int_value(1),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "info", "ดูข้อมูลการ์ตูน")
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "manga",
+                        "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                    )
+                    .required(true)
+                    .set_autocomplete(true),
+                ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "unread",
+            "ดูการ์ตูนที่ยังอ่านไม่ทันตอนล่าสุด",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "remove",
+                "เลิกติดตามการ์ตูนในเซิร์ฟเวอร์นี้ (ลบออกจากระบบเมื่อไม่มีผู้ติดตามแล้ว)",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            ),
+        )
+}
+
+// ข้อความเมื่อ find_manga ไม่พบการ์ตูน
+pub const MANGA_NOT_FOUND: &str = "ไม่พบการ์ตูนนี้ในระบบ กรุณาเลือกจากรายการที่แสดงขณะพิมพ์";
+
+// ค้นหาการ์ตูนจากรหัส (ObjectId ที่ได้จาก autocomplete) URL หรือชื่อเรื่องที่ตรงกันทุกตัวอักษร
+// ไม่เดาจากชื่อที่ใกล้เคียง เพื่อไม่ให้คำสั่งไปทำกับเรื่องที่ผู้ใช้ไม่ได้ตั้งใจ
+pub async fn find_manga(query: &str) -> Result<Option<Manga>, mongodb::error::Error> {
+    let query = query.trim();
+    if let Ok(id) = ObjectId::parse_str(query) {
+        return MangaService::get_by_id(&id).await;
+    }
+    if query.starts_with("https://") || query.starts_with("http://") {
+        return MangaService::get_by_url(query).await;
+    }
+    MangaService::get_by_exact_title(query).await
+}
+
+// ตอบ autocomplete ของ option ที่ใช้ระบุการ์ตูน โดยค้นจากชื่อเรื่องและชื่ออื่น
+// ค่าที่ส่งกลับเป็นรหัสของการ์ตูน ซึ่ง find_manga รองรับ
+pub async fn autocomplete_manga(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+) -> serenity::Result<()> {
+    let Some(focused) = interaction.data.autocomplete() else {
+        return Ok(());
+    };
+
+    let mangas = match MangaService::search(focused.value, MAX_AUTOCOMPLETE_CHOICES).await {
+        Ok(mangas) => mangas,
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการค้นหาการ์ตูน: {e:?}");
+            Vec::new()
+        }
+    };
+
+    let response = mangas
+        .iter()
+        .filter_map(|manga| {
+            let id = manga.id?;
+            let name: String = format!("{} (ตอนที่ {})", manga.title, manga.latest_chapter)
+                .chars()
+                .take(MAX_CHOICE_NAME_LENGTH)
+                .collect();
+            Some((name, id.to_hex()))
+        })
+        .fold(CreateAutocompleteResponse::new(), |response, (name, id)| {
+            response.add_string_choice(name, id)
+        });
+
+    interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+        .await
 }
 
 // UI Utility Function
@@ -47,6 +168,68 @@ pub async fn show_manga_info_ui(
         .await
 }
 
+// UI Utility Function สำหรับคำสั่งที่ defer ไว้แล้ว
+pub async fn edit_manga_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบจัดการการ์ตูน"));
+
+    command
+        .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+// embed แสดงข้อมูลการ์ตูนพร้อมรูปปก ตอนล่าสุด และตอนล่าสุดบางส่วน
+fn build_manga_preview_embed(
+    manga: &Manga,
+    result: &ScrapeResult,
+    source_name: &str,
+) -> CreateEmbed {
+    let recent_chapters = result
+        .chapters
+        .iter()
+        .rev()
+        .take(5)
+        .map(|chapter| match &chapter.release_date {
+            Some(date) => format!("• [ตอนที่ {}]({}) - {}", chapter.number, chapter.url, date),
+            None => format!("• [ตอนที่ {}]({})", chapter.number, chapter.url),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let embed = CreateEmbed::new()
+        .url(&manga.url)
+        .description(recent_chapters)
+        .field(
+            "ตอนล่าสุด",
+            format!(
+                "[ตอนที่ {}]({})",
+                manga.latest_chapter, manga.latest_chapter_url
+            ),
+            true,
+        )
+        .field("จำนวนตอน", result.chapters.len().to_string(), true)
+        .field("สถานะ", result.status.as_deref().unwrap_or("-"), true)
+        .field("แหล่งที่มา", source_name, true)
+        .color(Colour::DARK_GREEN)
+        .footer(CreateEmbedFooter::new("ระบบจัดการการ์ตูน"));
+
+    if let Some(image_url) = &manga.image_url {
+        embed.image(image_url)
+    } else {
+        embed
+    }
+}
+
 async fn add_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
     let url: &str = command
         .data
@@ -76,70 +259,844 @@ async fn add_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Res
         .await;
     }
 
+    // ตรวจสอบว่ามีอะแดปเตอร์รองรับเว็บไซต์นี้หรือไม่
+    let Some(source) = sources::registry().find_by_url(url) else {
+        return show_manga_info_ui(
+            command,
+            ctx,
+            "ไม่รองรับเว็บไซต์นี้",
+            "ยังไม่มีอะแดปเตอร์สำหรับเว็บไซต์ของ URL นี้",
+            Colour::RED,
+        )
+        .await;
+    };
+
     // ตรวจสอบว่ามีการ์ตูนนี้ในฐานข้อมูลหรือไม่
     match MangaService::get_by_url(url).await {
-        Ok(Some(_)) => {
+        Ok(Some(existing)) => {
+            let description = follow_existing_manga(command, &existing).await;
+            return show_manga_info_ui(command, ctx, "การเพิ่มการ์ตูน", &description, Colour::GOLD)
+                .await;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return show_manga_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการตรวจสอบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    }
+
+    // การดึงข้อมูลจากเว็บไซต์อาจใช้เวลานานกว่าที่ Discord รอ จึงตอบกลับแบบ defer ก่อน
+    command.defer_ephemeral(&ctx.http).await?;
+
+    let result = match source.scrape(url).await {
+        Ok(result) => result,
+        Err(e) => {
+            return edit_manga_info_ui(
+                command,
+                ctx,
+                "ไม่สามารถดึงข้อมูลการ์ตูนได้",
+                &format!("ไม่สามารถดึงข้อมูลจาก {} ได้: {e}", source.name()),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+
+    let Some(latest) = result.latest_chapter() else {
+        return edit_manga_info_ui(
+            command,
+            ctx,
+            "ไม่สามารถดึงข้อมูลการ์ตูนได้",
+            "ไม่พบตอนของการ์ตูนเรื่องนี้",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    // สร้างข้อมูลการ์ตูนจากข้อมูลจริงบนเว็บไซต์
+    let manga: Manga = Manga {
+        alt_titles: result.alt_titles.clone(),
+        ..Manga::new(
+            result.title.clone(),
+            url.to_string(),
+            latest.number.clone(),
+            latest.url.clone(),
+            result.cover_url.clone(),
+        )
+    };
+
+    // บันทึกลงฐานข้อมูล
+    let manga_id = match MangaService::create(&manga).await {
+        Ok(manga_id) => manga_id,
+        // มีคนเพิ่มเรื่องเดียวกันระหว่างที่กำลังดึงข้อมูล
+        Err(e) if mongo::is_duplicate_key_error(&e) => {
+            let description = match MangaService::get_by_url(url).await {
+                Ok(Some(existing)) => follow_existing_manga(command, &existing).await,
+                _ => "การ์ตูนนี้มีอยู่ในระบบแล้ว ใช้ /subscription follow เพื่อติดตามในเซิร์ฟเวอร์นี้".to_string(),
+            };
+            return edit_manga_info_ui(command, ctx, "การเพิ่มการ์ตูน", &description, Colour::GOLD)
+                .await;
+        }
+        Err(e) => {
+            return edit_manga_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+
+    // บันทึกตอนที่มีอยู่แล้วทั้งหมดเป็นประวัติ รอบอัพเดทถัดไปจะได้ไม่แจ้งเตือนตอนเก่า
+    if let Err(e) =
+        ChapterService::record_chapters(&manga_id, &manga.title, &result.chapters, &ObjectId::new())
+            .await
+    {
+        println!("เกิดข้อผิดพลาดในการบันทึกประวัติตอน: {e:?}");
+    }
+
+    // เซิร์ฟเวอร์ที่เพิ่มการ์ตูนจะติดตามเรื่องนี้ทันที
+    if let Some(guild_id) = command.guild_id {
+        let subscription =
+            GuildSubscription::new(guild_id.to_string(), manga_id, manga.title.clone(), None);
+        if let Err(e) = GuildSubscriptionService::subscribe(&subscription).await {
+            println!("เกิดข้อผิดพลาดในการบันทึกการติดตาม: {e:?}");
+        }
+    }
+
+    let embed = build_manga_preview_embed(&manga, &result, source.name())
+        .title(format!("เพิ่มการ์ตูนสำเร็จ: {}", manga.title));
+
+    command
+        .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+// การ์ตูนที่มีอยู่ในระบบแล้วให้เซิร์ฟเวอร์ที่สั่งเพิ่มติดตามแทน คืนข้อความสำหรับตอบกลับ
+async fn follow_existing_manga(command: &CommandInteraction, manga: &Manga) -> String {
+    let (Some(guild_id), Some(manga_id)) = (command.guild_id, manga.id) else {
+        return format!("**{}** มีอยู่ในระบบแล้ว", manga.title);
+    };
+    let subscription =
+        GuildSubscription::new(guild_id.to_string(), manga_id, manga.title.clone(), None);
+    match GuildSubscriptionService::subscribe(&subscription).await {
+        Ok(true) => format!(
+            "**{}** มีอยู่ในระบบแล้ว เซิร์ฟเวอร์นี้ติดตามเรื่องนี้เรียบร้อย",
+            manga.title
+        ),
+        Ok(false) => format!("**{}** มีอยู่ในระบบแล้ว และเซิร์ฟเวอร์นี้ติดตามอยู่แล้ว", manga.title),
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการบันทึกการติดตาม: {e:?}");
+            format!(
+                "**{}** มีอยู่ในระบบแล้ว ใช้ /subscription follow เพื่อติดตามในเซิร์ฟเวอร์นี้",
+                manga.title
+            )
+        }
+    }
+}
+
+// สร้าง embed และปุ่มเปลี่ยนหน้าของรายการการ์ตูน (page เริ่มที่ 0)
+async fn build_list_page(
+    page: u64,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>), mongodb::error::Error> {
+    let total = MangaService::count().await?;
+    let total_pages = total.div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(total_pages - 1);
+    let mangas = MangaService::get_page(page, PAGE_SIZE).await?;
+
+    let description = if mangas.is_empty() {
+        "ยังไม่มีการ์ตูนในระบบ".to_string()
+    } else {
+        mangas
+            .iter()
+            .enumerate()
+            .map(|(index, manga)| {
+                format!(
+                    "`{}.` [{}]({}) - ตอนที่ {}",
+                    page * PAGE_SIZE + index as u64 + 1,
+                    manga.title,
+                    manga.url,
+                    manga.latest_chapter
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let embed = CreateEmbed::new()
+        .title("รายการการ์ตูนทั้งหมด")
+        .description(description)
+        .color(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "หน้า {}/{} • ทั้งหมด {} เรื่อง",
+            page + 1,
+            total_pages,
+            total
+        )));
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("manga_list:{}", page.saturating_sub(1)))
+            .label("◀ ก่อนหน้า")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("manga_list:{}", page + 1))
+            .label("ถัดไป ▶")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= total_pages),
+    ]);
+
+    Ok((embed, vec![buttons]))
+}
+
+async fn list_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let page = get_sub_option(command, "list", "page")
+        .and_then(|value| value.as_i64())
+        .map(|page| page.max(1) as u64 - 1)
+        .unwrap_or(0);
+
+    match build_list_page(page).await {
+        Ok((embed, components)) => {
+            command
+                .create_response(
+                    &ctx.http,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .embed(embed)
+                            .components(components)
+                            .ephemeral(true),
+                    ),
+                )
+                .await
+        }
+        Err(e) => {
             show_manga_info_ui(
                 command,
                 ctx,
-                "การเพิ่มการ์ตูน",
-                "การ์ตูนนี้มีอยู่ในระบบแล้ว",
-                Colour::GOLD,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
             )
             .await
         }
+    }
+}
+
+async fn manga_info(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let query = get_sub_option(command, "info", "manga")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    let manga = match find_manga(query).await {
+        Ok(Some(manga)) => manga,
         Ok(None) => {
-            // สร้างข้อมูลการ์ตูนใหม่
-            let manga: Manga = Manga::new(
-                "Untitled".to_string(), // ตั้งชื่อชั่วคราว
-                url.to_string(),
-                0,               // เริ่มต้นที่ตอนที่ 0
-                url.to_string(), // ใช้ URL เดิมเป็น chapter URL
-                None,            // ไม่มีรูปภาพ
-            );
-
-            // บันทึกลงฐานข้อมูล
-            match MangaService::create(&manga).await {
-                Ok(_) => {
-                    let description = format!(
-                        "**เพิ่มการ์ตูนสำเร็จ**\n\
-                        **ชื่อเรื่อง:** {}\n\
-                        **ตอนล่าสุด:** {:?}\n\
-                        **URL:** {}",
-                        manga.title, manga.latest_chapter, manga.url
-                    );
-
-                    show_manga_info_ui(
-                        command,
-                        ctx,
-                        "เพิ่มการ์ตูนสำเร็จ",
-                        &description,
-                        Colour::DARK_GREEN,
-                    )
-                    .await
-                }
-                Err(e) => {
-                    show_manga_info_ui(
-                        command,
-                        ctx,
-                        "เกิดข้อผิดพลาด",
-                        &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {}", e),
-                        Colour::RED,
-                    )
-                    .await
+            return show_manga_info_ui(command, ctx, "ไม่พบข้อมูล", MANGA_NOT_FOUND, Colour::RED)
+                .await;
+        }
+        Err(e) => {
+            return show_manga_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+
+    let source_name = sources::registry()
+        .find_by_url(&manga.url)
+        .map(|source| source.name().to_string())
+        .unwrap_or_else(|| "ไม่รองรับ".to_string());
+
+    let chapter_count = match &manga.id {
+        Some(manga_id) => ChapterService::count_by_manga(manga_id).await.unwrap_or(0),
+        None => 0,
+    };
+
+    // รูปแบบการออกตอนที่เรียนรู้จากประวัติ แสดงเวลาตามเขตเวลาของเซิร์ฟเวอร์
+    let pattern = match &manga.id {
+        Some(manga_id) => ChapterService::get_release_times(manga_id)
+            .await
+            .ok()
+            .and_then(|times| ReleasePattern::learn(&times)),
+        None => None,
+    };
+    let settings = match command.guild_id {
+        Some(guild_id) => GuildSettingsService::get(&guild_id.to_string())
+            .await
+            .unwrap_or_else(|_| GuildSettings::new(guild_id.to_string())),
+        None => GuildSettings::new(String::new()),
+    };
+    let release_pattern = match pattern {
+        Some(pattern) if pattern.is_on_hiatus(chrono::Utc::now()) => format!(
+            "{} (ไม่มีตอนใหม่นานแล้ว อาจพักหรือจบแล้ว)",
+            pattern.describe(&settings.tz())
+        ),
+        Some(pattern) => format!(
+            "{}\nคาดว่าตอนถัดไป <t:{}:R>",
+            pattern.describe(&settings.tz()),
+            pattern.expected_next(chrono::Utc::now()).timestamp()
+        ),
+        None => "ยังมีประวัติไม่พอ".to_string(),
+    };
+
+    let embed = CreateEmbed::new()
+        .title(&manga.title)
+        .url(&manga.url)
+        .field(
+            "ตอนล่าสุด",
+            format!(
+                "[ตอนที่ {}]({})",
+                manga.latest_chapter, manga.latest_chapter_url
+            ),
+            true,
+        )
+        .field("จำนวนตอนที่บันทึก", chapter_count.to_string(), true)
+        .field("แหล่งที่มา", source_name, true)
+        .field(
+            "เช็คอัพเดทล่าสุด",
+            manga
+                .last_checked_at
+                .as_ref()
+                .map(discord_timestamp)
+                .unwrap_or_else(|| "ยังไม่เคยเช็ค".to_string()),
+            true,
+        )
+        .field(
+            "เช็คครั้งถัดไป",
+            format!(
+                "{} (ทุก {})",
+                manga
+                    .next_check_at
+                    .as_ref()
+                    .map(discord_timestamp)
+                    .unwrap_or_else(|| "ยังไม่ได้ตั้งเวลา".to_string()),
+                if manga.check_interval < 3600 {
+                    format!("{} นาที", manga.check_interval / 60)
+                } else {
+                    format!("{} ชั่วโมง", manga.check_interval / 3600)
                 }
-            }
+            ),
+            true,
+        )
+        .field("รอบการออกตอน", release_pattern, true)
+        .field("เพิ่มเมื่อ", discord_timestamp(&manga.created_at), true)
+        .color(Colour::BLUE)
+        .footer(CreateEmbedFooter::new(format!(
+            "รหัส: {}",
+            manga.id.map(|id| id.to_hex()).unwrap_or_default()
+        )));
+
+    // แสดงสถานะเมื่อเช็คอัพเดทไม่สำเร็จติดต่อกัน
+    let embed = match &manga.failing_since {
+        Some(failing_since) if manga.consecutive_failures > 0 => embed
+            .field(
+                "เช็คไม่สำเร็จ",
+                format!(
+                    "{} ครั้งติดต่อกัน ตั้งแต่ {}\n{}",
+                    manga.consecutive_failures,
+                    discord_timestamp(failing_since),
+                    manga
+                        .last_error
+                        .as_deref()
+                        .unwrap_or_default()
+                        .chars()
+                        .take(200)
+                        .collect::<String>()
+                ),
+                false,
+            )
+            .color(Colour::ORANGE),
+        _ => embed,
+    };
+
+    let embed = if let Some(image_url) = &manga.image_url {
+        embed.thumbnail(image_url)
+    } else {
+        embed
+    };
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+async fn remove_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    if command.guild_id.is_none() {
+        return show_manga_info_ui(
+            command,
+            ctx,
+            "ใช้ไม่ได้",
+            "คำสั่งนี้ใช้ได้เฉพาะในเซิร์ฟเวอร์",
+            Colour::RED,
+        )
+        .await;
+    }
+
+    // เลิกติดตามในนามของเซิร์ฟเวอร์ จึงจำกัดให้ผู้ที่จัดการเซิร์ฟเวอร์ได้เท่านั้น
+    let can_manage = command
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_guild());
+    if !can_manage {
+        return show_manga_info_ui(
+            command,
+            ctx,
+            "ไม่มีสิทธิ์",
+            "ต้องมีสิทธิ์จัดการเซิร์ฟเวอร์จึงจะเลิกติดตามการ์ตูนได้",
+            Colour::RED,
+        )
+        .await;
+    }
+
+    let query = get_sub_option(command, "remove", "manga")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    let manga = match find_manga(query).await {
+        Ok(Some(manga)) => manga,
+        Ok(None) => {
+            return show_manga_info_ui(command, ctx, "ไม่พบข้อมูล", MANGA_NOT_FOUND, Colour::RED)
+                .await;
         }
         Err(e) => {
-            show_manga_info_ui(
+            return show_manga_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+    let manga_id = manga.id.map(|id| id.to_hex()).unwrap_or_default();
+
+    let embed = CreateEmbed::new()
+        .title("ยืนยันการเลิกติดตามการ์ตูน")
+        .description(format!(
+            "ต้องการเลิกติดตาม **{}** ในเซิร์ฟเวอร์นี้หรือไม่?\nถ้าไม่มีเซิร์ฟเวอร์หรือผู้ใช้อื่นติดตามแล้ว การ์ตูนและประวัติตอนจะถูกลบออกจากระบบ",
+            manga.title
+        ))
+        .color(Colour::GOLD)
+        .footer(CreateEmbedFooter::new("ระบบจัดการการ์ตูน"));
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("manga_remove_confirm:{manga_id}"))
+            .label("เลิกติดตาม")
+            .style(ButtonStyle::Danger),
+        CreateButton::new("manga_remove_cancel")
+            .label("ยกเลิก")
+            .style(ButtonStyle::Secondary),
+    ]);
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![buttons])
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// แก้ไขข้อความเดิมของปุ่มเป็นผลลัพธ์ และนำปุ่มออก
+async fn update_component_ui(
+    component: &ComponentInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบจัดการการ์ตูน"));
+
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![]),
+            ),
+        )
+        .await
+}
+
+async fn confirm_remove_manga(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    manga_id: &str,
+) -> serenity::Result<()> {
+    let Ok(manga_id) = ObjectId::parse_str(manga_id) else {
+        return update_component_ui(
+            component,
+            ctx,
+            "เกิดข้อผิดพลาด",
+            "รหัสการ์ตูนไม่ถูกต้อง",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    let Some(guild_id) = component.guild_id.map(|id| id.to_string()) else {
+        return update_component_ui(
+            component,
+            ctx,
+            "ใช้ไม่ได้",
+            "คำสั่งนี้ใช้ได้เฉพาะในเซิร์ฟเวอร์",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    // เลิกติดตามและลบข้อมูลของเซิร์ฟเวอร์นี้เท่านั้น ข้อมูลของเซิร์ฟเวอร์อื่นไม่ถูกแตะต้อง
+    if let Err(e) = GuildSubscriptionService::unsubscribe(&guild_id, &manga_id, None).await {
+        return update_component_ui(
+            component,
+            ctx,
+            "เกิดข้อผิดพลาด",
+            &format!("เกิดข้อผิดพลาดในการเลิกติดตาม: {e}"),
+            Colour::RED,
+        )
+        .await;
+    }
+    if let Err(e) = MangaRoleService::unlink(&guild_id, &manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบการผูก role: {e:?}");
+    }
+    if let Err(e) = PendingNotificationService::delete_by_guild_manga(&guild_id, &manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบตอนที่รอส่งในสรุป: {e:?}");
+    }
+    if let Err(e) = MangaThreadService::delete_by_guild_manga(&guild_id, &manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบเธรดของการ์ตูน: {e:?}");
+    }
+
+    // ลบการ์ตูนออกจากระบบเมื่อไม่มีเซิร์ฟเวอร์หรือผู้ใช้คนใดติดตามแล้ว
+    let followers = match (
+        GuildSubscriptionService::count_by_manga(&manga_id).await,
+        UserSubscriptionService::count_by_manga(&manga_id).await,
+    ) {
+        (Ok(guilds), Ok(users)) => guilds + users,
+        (Err(e), _) | (_, Err(e)) => {
+            println!("เกิดข้อผิดพลาดในการนับผู้ติดตามการ์ตูน: {e:?}");
+            1
+        }
+    };
+    if followers > 0 {
+        return update_component_ui(
+            component,
+            ctx,
+            "เลิกติดตามสำเร็จ",
+            "เลิกติดตามการ์ตูนเรื่องนี้ในเซิร์ฟเวอร์นี้แล้ว",
+            Colour::DARK_GREEN,
+        )
+        .await;
+    }
+
+    match delete_manga(&manga_id).await {
+        Ok(false) => {
+            update_component_ui(component, ctx, "ไม่พบข้อมูล", "การ์ตูนนี้ถูกลบไปแล้ว", Colour::GOLD).await
+        }
+        Ok(true) => {
+            update_component_ui(
+                component,
+                ctx,
+                "เลิกติดตามสำเร็จ",
+                "เลิกติดตามการ์ตูนเรื่องนี้แล้ว และลบออกจากระบบเนื่องจากไม่มีผู้ติดตามแล้ว",
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            update_component_ui(
+                component,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการลบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+// ลบการ์ตูนและข้อมูลทั้งหมดที่เกี่ยวข้องออกจากระบบ คืน false ถ้าการ์ตูนถูกลบไปแล้ว
+async fn delete_manga(manga_id: &ObjectId) -> Result<bool, mongodb::error::Error> {
+    if !MangaService::delete_by_id(manga_id).await? {
+        return Ok(false);
+    }
+    if let Err(e) = ChapterService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบประวัติตอน: {e:?}");
+    }
+    if let Err(e) = GuildSubscriptionService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบการติดตาม: {e:?}");
+    }
+    if let Err(e) = UserSubscriptionService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบการติดตามทาง DM: {e:?}");
+    }
+    if let Err(e) = ReadingProgressService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบความคืบหน้าการอ่าน: {e:?}");
+    }
+    if let Err(e) = MangaRoleService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบการผูก role: {e:?}");
+    }
+    if let Err(e) = PendingNotificationService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบตอนที่รอส่งในสรุป: {e:?}");
+    }
+    if let Err(e) = MangaThreadService::delete_by_manga(manga_id).await {
+        println!("เกิดข้อผิดพลาดในการลบเธรดของการ์ตูน: {e:?}");
+    }
+    Ok(true)
+}
+
+// รายการการ์ตูนที่ผู้ใช้อ่านไม่ทันตอนล่าสุด พร้อมจำนวนตอนที่ยังไม่ได้อ่าน
+async fn list_unread(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    command.defer_ephemeral(&ctx.http).await?;
+
+    let progress = match ReadingProgressService::get_by_user(&command.user.id.to_string()).await {
+        Ok(progress) => progress,
+        Err(e) => {
+            return edit_manga_info_ui(
                 command,
                 ctx,
                 "เกิดข้อผิดพลาด",
-                &format!("เกิดข้อผิดพลาดในการตรวจสอบข้อมูล: {}", e),
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+
+    if progress.is_empty() {
+        return edit_manga_info_ui(
+            command,
+            ctx,
+            "การ์ตูนที่ยังไม่ได้อ่าน",
+            "ยังไม่มีประวัติการอ่าน กดปุ่ม \"อ่านแล้ว\" ในข้อความแจ้งเตือนตอนใหม่เพื่อเริ่มบันทึก",
+            Colour::GOLD,
+        )
+        .await;
+    }
+
+    let mut lines = Vec::new();
+    for progress in progress {
+        let manga = match MangaService::get_by_id(&progress.manga_id).await {
+            Ok(Some(manga)) => manga,
+            Ok(None) => continue,
+            Err(e) => {
+                println!("เกิดข้อผิดพลาดในการดึงข้อมูลการ์ตูน: {e:?}");
+                continue;
+            }
+        };
+        let unread =
+            match ChapterService::count_newer_than(&progress.manga_id, &progress.last_read_chapter)
+                .await
+            {
+                Ok(unread) => unread,
+                Err(e) => {
+                    println!("เกิดข้อผิดพลาดในการนับตอนที่ยังไม่ได้อ่าน: {e:?}");
+                    continue;
+                }
+            };
+        // มังงะที่ยังไม่มีประวัติตอนนับได้เพียงว่ามีตอนใหม่กว่า
+        let unread = if unread == 0 && manga.latest_chapter > progress.last_read_chapter {
+            1
+        } else {
+            unread
+        };
+        if unread == 0 {
+            continue;
+        }
+
+        lines.push(format!(
+            "• [**{}**]({}) ยังไม่ได้อ่าน {} ตอน (อ่านถึงตอนที่ {} ล่าสุดตอนที่ {})",
+            manga.title,
+            manga.latest_chapter_url,
+            unread,
+            progress.last_read_chapter,
+            manga.latest_chapter
+        ));
+    }
+
+    if lines.is_empty() {
+        return edit_manga_info_ui(
+            command,
+            ctx,
+            "การ์ตูนที่ยังไม่ได้อ่าน",
+            "อ่านทันทุกเรื่องแล้ว",
+            Colour::DARK_GREEN,
+        )
+        .await;
+    }
+
+    let total = lines.len();
+    let mut description = lines
+        .into_iter()
+        .take(MAX_UNREAD_LISTED)
+        .collect::<Vec<String>>()
+        .join("\n");
+    if total > MAX_UNREAD_LISTED {
+        description.push_str(&format!("\nและอีก {} เรื่อง", total - MAX_UNREAD_LISTED));
+    }
+
+    edit_manga_info_ui(
+        command,
+        ctx,
+        &format!("การ์ตูนที่ยังไม่ได้อ่าน ({total} เรื่อง)"),
+        &description,
+        Colour::BLUE,
+    )
+    .await
+}
+
+// บันทึกว่าผู้ใช้ที่กดปุ่มอ่านถึงตอนนี้แล้ว (value รูปแบบ <รหัสการ์ตูน>:<เลขตอน>)
+// ข้อความแจ้งเตือนใช้ร่วมกันหลายคน จึงตอบกลับเฉพาะผู้กดโดยไม่แก้ข้อความเดิม
+async fn mark_chapter_read(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    value: &str,
+) -> serenity::Result<()> {
+    let parsed = value.split_once(':').and_then(|(manga_id, number)| {
+        Some((
+            ObjectId::parse_str(manga_id).ok()?,
+            ChapterNumber::parse(number)?,
+        ))
+    });
+
+    let (title, description, color) = match parsed {
+        None => (
+            "เกิดข้อผิดพลาด".to_string(),
+            "ข้อมูลของปุ่มไม่ถูกต้อง".to_string(),
+            Colour::RED,
+        ),
+        Some((manga_id, number)) => match MangaService::get_by_id(&manga_id).await {
+            Ok(Some(manga)) => {
+                match ReadingProgressService::mark_read(
+                    &component.user.id.to_string(),
+                    &manga_id,
+                    &manga.title,
+                    &number,
+                )
+                .await
+                {
+                    Ok(last_read) if last_read > number => (
+                        "บันทึกการอ่านแล้ว".to_string(),
+                        format!("คุณอ่าน **{}** ถึงตอนที่ {last_read} อยู่แล้ว", manga.title),
+                        Colour::BLUE,
+                    ),
+                    Ok(last_read) => (
+                        "บันทึกการอ่านแล้ว".to_string(),
+                        format!("บันทึกว่าอ่าน **{}** ถึงตอนที่ {last_read} แล้ว", manga.title),
+                        Colour::DARK_GREEN,
+                    ),
+                    Err(e) => (
+                        "เกิดข้อผิดพลาด".to_string(),
+                        format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                        Colour::RED,
+                    ),
+                }
+            }
+            Ok(None) => (
+                "ไม่พบข้อมูล".to_string(),
+                "การ์ตูนนี้ถูกลบออกจากระบบแล้ว".to_string(),
+                Colour::GOLD,
+            ),
+            Err(e) => (
+                "เกิดข้อผิดพลาด".to_string(),
+                format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
                 Colour::RED,
+            ),
+        },
+    };
+
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบจัดการการ์ตูน"));
+
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// จัดการปุ่มของคำสั่ง manga (custom_id รูปแบบ manga_<action>:<ค่า>)
+pub async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> serenity::Result<()> {
+    let custom_id = component.data.custom_id.as_str();
+    let (action, value) = custom_id.split_once(':').unwrap_or((custom_id, ""));
+
+    match action {
+        "manga_list" => match build_list_page(value.parse().unwrap_or(0)).await {
+            Ok((embed, components)) => {
+                component
+                    .create_response(
+                        &ctx.http,
+                        CreateInteractionResponse::UpdateMessage(
+                            CreateInteractionResponseMessage::new()
+                                .embed(embed)
+                                .components(components),
+                        ),
+                    )
+                    .await
+            }
+            Err(e) => {
+                update_component_ui(
+                    component,
+                    ctx,
+                    "เกิดข้อผิดพลาด",
+                    &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                    Colour::RED,
+                )
+                .await
+            }
+        },
+        "manga_remove_confirm" => confirm_remove_manga(ctx, component, value).await,
+        "manga_read" => mark_chapter_read(ctx, component, value).await,
+        "manga_remove_cancel" => {
+            update_component_ui(
+                component,
+                ctx,
+                "ยกเลิกการลบ",
+                "ยังคงติดตามการ์ตูนเรื่องนี้ต่อ",
+                Colour::BLUE,
             )
             .await
         }
+        _ => Ok(()),
     }
 }
 
@@ -153,6 +1110,10 @@ pub async fn run(
 
     match subcommand_name.as_str() {
         "add" => add_manga(ctx, command).await,
+        "list" => list_manga(ctx, command).await,
+        "info" => manga_info(ctx, command).await,
+        "remove" => remove_manga(ctx, command).await,
+        "unread" => list_unread(ctx, command).await,
         _ => show_manga_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
     }
 }
@@ -0,0 +1,351 @@
+use super::get_sub_option;
+use crate::models::guild_settings::{GuildSettings, QuietHours};
+use crate::service::guild_settings_service::GuildSettingsService;
+use crate::utils::time;
+use chrono_tz::TZ_VARIANTS;
+use serenity::all::{
+    Colour, CommandInteraction, CommandOptionType, Context, CreateAutocompleteResponse,
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, Permissions,
+};
+
+// จำนวนตัวเลือกสูงสุดที่ Discord ยอมให้ส่งใน autocomplete
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("settings")
+        .description("ตั้งค่าเขตเวลา ภาษา และช่วงงดแจ้งเตือนของเซิร์ฟเวอร์")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "view",
+            "ดูการตั้งค่าของเซิร์ฟเวอร์",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "timezone",
+                "ตั้งเขตเวลาของเซิร์ฟเวอร์",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "name",
+                    "ชื่อเขตเวลา เช่น Asia/Bangkok",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "locale", "ตั้งภาษาของเซิร์ฟเวอร์")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "language", "ภาษา")
+                        .required(true)
+                        .add_string_choice("ไทย", "th")
+                        .add_string_choice("English", "en"),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "quiet-hours",
+                "พักการแจ้งเตือนในช่วงเวลานี้ แล้วส่งเมื่อพ้นช่วง",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "start", "ชั่วโมงที่เริ่มงด (0-23)")
+                    .required(true)
+                    .min_int_value(0)
+                    .max_int_value(23),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "end",
+                    "ชั่วโมงที่กลับมาแจ้งเตือน (0-23)",
+                )
+                .required(true)
+                .min_int_value(0)
+                .max_int_value(23),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "quiet-hours-off",
+            "ยกเลิกช่วงงดแจ้งเตือน",
+        ))
+}
+
+// UI Utility Function
+pub async fn show_settings_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบตั้งค่าเซิร์ฟเวอร์"));
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// เสนอชื่อเขตเวลาที่มีคำที่พิมพ์อยู่
+pub async fn autocomplete_timezone(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+) -> serenity::Result<()> {
+    let Some(focused) = interaction.data.autocomplete() else {
+        return Ok(());
+    };
+    let query = focused.value.to_lowercase();
+
+    let response = TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name())
+        .filter(|name| name.to_lowercase().contains(&query))
+        .take(MAX_AUTOCOMPLETE_CHOICES)
+        .fold(CreateAutocompleteResponse::new(), |response, name| {
+            response.add_string_choice(name, name)
+        });
+
+    interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+        .await
+}
+
+fn describe_settings(settings: &GuildSettings) -> String {
+    let quiet_hours = match &settings.quiet_hours {
+        Some(quiet) => format!("{:02}:00 - {:02}:00", quiet.start_hour, quiet.end_hour),
+        None => "ไม่ได้ตั้ง".to_string(),
+    };
+    let local_now = chrono::Utc::now().with_timezone(&settings.tz());
+
+    format!(
+        "**เขตเวลา:** {} (ขณะนี้ {})\n**ภาษา:** {}\n**ช่วงงดแจ้งเตือน:** {}",
+        settings.timezone,
+        local_now.format("%H:%M"),
+        settings.locale,
+        quiet_hours
+    )
+}
+
+async fn view_settings(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+
+    match GuildSettingsService::get(&guild_id).await {
+        Ok(settings) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "การตั้งค่าเซิร์ฟเวอร์",
+                &describe_settings(&settings),
+                Colour::BLUE,
+            )
+            .await
+        }
+        Err(e) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn set_timezone(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let name = get_sub_option(command, "timezone", "name")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    let Some(tz) = time::parse_timezone(name) else {
+        return show_settings_info_ui(
+            command,
+            ctx,
+            "ไม่รู้จักเขตเวลา",
+            &format!("ไม่พบเขตเวลา `{name}` กรุณาเลือกจากรายการ เช่น Asia/Bangkok"),
+            Colour::GOLD,
+        )
+        .await;
+    };
+
+    match GuildSettingsService::set_timezone(&guild_id, tz.name()).await {
+        Ok(()) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "ตั้งเขตเวลาสำเร็จ",
+                &format!(
+                    "ใช้เขตเวลา **{}** สำหรับช่วงงดแจ้งเตือนและรอบส่งสรุปของเซิร์ฟเวอร์นี้",
+                    tz.name()
+                ),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn set_locale(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let locale = get_sub_option(command, "locale", "language")
+        .and_then(|value| value.as_str())
+        .unwrap_or("th");
+
+    match GuildSettingsService::set_locale(&guild_id, locale).await {
+        Ok(()) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "ตั้งภาษาสำเร็จ",
+                &format!("ใช้ภาษา **{locale}** สำหรับเซิร์ฟเวอร์นี้"),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn set_quiet_hours(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let start_hour = get_sub_option(command, "quiet-hours", "start")
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0) as u32;
+    let end_hour = get_sub_option(command, "quiet-hours", "end")
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0) as u32;
+
+    if start_hour == end_hour {
+        return show_settings_info_ui(
+            command,
+            ctx,
+            "ช่วงเวลาไม่ถูกต้อง",
+            "ชั่วโมงที่เริ่มงดและชั่วโมงที่กลับมาแจ้งเตือนต้องไม่ใช่ชั่วโมงเดียวกัน",
+            Colour::GOLD,
+        )
+        .await;
+    }
+
+    let quiet_hours = QuietHours {
+        start_hour,
+        end_hour,
+    };
+    match GuildSettingsService::set_quiet_hours(&guild_id, Some(&quiet_hours)).await {
+        Ok(()) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "ตั้งช่วงงดแจ้งเตือนสำเร็จ",
+                &format!(
+                    "พักการแจ้งเตือนตั้งแต่ {start_hour:02}:00 ถึง {end_hour:02}:00 ตามเขตเวลาของเซิร์ฟเวอร์\nตอนใหม่ระหว่างนี้จะถูกส่งเป็นสรุปเมื่อพ้นช่วง"
+                ),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn clear_quiet_hours(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+
+    match GuildSettingsService::set_quiet_hours(&guild_id, None).await {
+        Ok(()) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "ยกเลิกช่วงงดแจ้งเตือนแล้ว",
+                "ตอนที่พักไว้จะถูกส่งภายในหนึ่งนาที",
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_settings_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    _: &serenity::prelude::TypeMap,
+) -> serenity::Result<()> {
+    if command.guild_id.is_none() {
+        return show_settings_info_ui(
+            command,
+            ctx,
+            "ใช้ในเซิร์ฟเวอร์เท่านั้น",
+            "คำสั่งนี้ใช้ได้เฉพาะในเซิร์ฟเวอร์",
+            Colour::RED,
+        )
+        .await;
+    }
+
+    let subcommand = command.data.options.first().unwrap();
+    let subcommand_name = &subcommand.name;
+
+    match subcommand_name.as_str() {
+        "view" => view_settings(ctx, command).await,
+        "timezone" => set_timezone(ctx, command).await,
+        "locale" => set_locale(ctx, command).await,
+        "quiet-hours" => set_quiet_hours(ctx, command).await,
+        "quiet-hours-off" => clear_quiet_hours(ctx, command).await,
+        _ => show_settings_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
+    }
+}
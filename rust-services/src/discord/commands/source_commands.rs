@@ -0,0 +1,105 @@
+use crate::sources;
+use serenity::all::{
+    Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+    CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    Permissions,
+};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("source")
+        .description("จัดการอะแดปเตอร์เว็บไซต์มังงะ")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "ดูรายการเว็บไซต์ที่รองรับ",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "reload",
+            "โหลดไฟล์ตั้งค่าเว็บไซต์ใหม่",
+        ))
+}
+
+// UI Utility Function
+pub async fn show_source_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบจัดการเว็บไซต์มังงะ"));
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+async fn list_sources(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let description = sources::registry()
+        .source_names()
+        .iter()
+        .map(|name| format!("• **{name}**"))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    show_source_info_ui(command, ctx, "เว็บไซต์ที่รองรับ", &description, Colour::BLUE).await
+}
+
+async fn reload_sources(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    match sources::reload().await {
+        Ok(registry) => {
+            let description = format!(
+                "โหลดอะแดปเตอร์ใหม่ {} รายการ\n{}",
+                registry.source_names().len(),
+                registry.source_names().join(", ")
+            );
+
+            show_source_info_ui(
+                command,
+                ctx,
+                "โหลดไฟล์ตั้งค่าสำเร็จ",
+                &description,
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_source_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("ไม่สามารถโหลดไฟล์ตั้งค่าได้ ยังคงใช้การตั้งค่าเดิม: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    _: &serenity::prelude::TypeMap,
+) -> serenity::Result<()> {
+    let subcommand = command.data.options.first().unwrap();
+    let subcommand_name = &subcommand.name;
+
+    match subcommand_name.as_str() {
+        "list" => list_sources(ctx, command).await,
+        "reload" => reload_sources(ctx, command).await,
+        _ => show_source_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
+    }
+}
@@ -0,0 +1,764 @@
+use super::get_sub_option;
+use super::manga_commands::{find_manga, MANGA_NOT_FOUND};
+use crate::models::guild_subscription::GuildSubscription;
+use crate::models::manga::Manga;
+use crate::models::manga_role::MangaRole;
+use crate::service::channels_service::ChannelsService;
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_role_service::MangaRoleService;
+use mongodb::bson::oid::ObjectId;
+use serenity::all::{
+    ChannelId, Colour, CommandInteraction, CommandOptionType, ComponentInteraction, Context,
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EditInteractionResponse, GuildId, Permissions, RoleId,
+};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("subscription")
+        .description("จัดการการ์ตูนที่เซิร์ฟเวอร์นี้ติดตาม")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "follow",
+                "ติดตามการ์ตูนในเซิร์ฟเวอร์นี้",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "ส่งเฉพาะช่องนี้ (ถ้าไม่ระบุจะส่งทุกช่องที่ลงทะเบียนไว้)",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "unfollow",
+                "เลิกติดตามการ์ตูนในเซิร์ฟเวอร์นี้",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "เลิกส่งเฉพาะช่องนี้ (ถ้าไม่ระบุจะเลิกติดตามทั้งเซิร์ฟเวอร์)",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "link-role",
+                "mention role นี้เมื่อการ์ตูนมีตอนใหม่",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Role, "role", "role ที่ต้องการ mention")
+                    .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "unlink-role",
+                "เลิก mention role เมื่อการ์ตูนมีตอนใหม่",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "manga",
+                    "ชื่อเรื่อง URL หรือรหัสของการ์ตูน",
+                )
+                .required(true)
+                .set_autocomplete(true),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "ดูรายการการ์ตูนที่เซิร์ฟเวอร์นี้ติดตาม",
+        ))
+}
+
+// UI Utility Function
+pub async fn show_subscription_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบติดตามการ์ตูน"));
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// UI Utility Function สำหรับคำสั่งที่ defer ไว้แล้ว
+async fn edit_subscription_info_ui(
+    command: &CommandInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบติดตามการ์ตูน"));
+
+    command
+        .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+// ดึงการ์ตูนจาก option manga ของคำสั่งย่อย ถ้าไม่พบจะตอบกลับผู้ใช้และคืน None
+async fn get_manga_option(
+    ctx: &Context,
+    command: &CommandInteraction,
+    subcommand: &str,
+) -> serenity::Result<Option<Manga>> {
+    let query = get_sub_option(command, subcommand, "manga")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    match find_manga(query).await {
+        Ok(Some(manga)) => Ok(Some(manga)),
+        Ok(None) => {
+            show_subscription_info_ui(command, ctx, "ไม่พบข้อมูล", MANGA_NOT_FOUND, Colour::RED)
+                .await?;
+            Ok(None)
+        }
+        Err(e) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+fn get_channel_option(command: &CommandInteraction, subcommand: &str) -> Option<ChannelId> {
+    get_sub_option(command, subcommand, "channel").and_then(|value| value.as_channel_id())
+}
+
+async fn follow_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let Some(manga) = get_manga_option(ctx, command, "follow").await? else {
+        return Ok(());
+    };
+    let channel_id = get_channel_option(command, "follow");
+
+    // ช่องที่ระบุต้องลงทะเบียนไว้ก่อน
+    if let Some(channel_id) = channel_id {
+        match ChannelsService::get_channels_by_guild(&guild_id).await {
+            Ok(channels)
+                if channels
+                    .iter()
+                    .any(|c| c.channel_id == channel_id.to_string()) => {}
+            Ok(_) => {
+                return show_subscription_info_ui(
+                    command,
+                    ctx,
+                    "ยังไม่ได้ลงทะเบียนช่อง",
+                    &format!("กรุณาลงทะเบียนช่อง <#{channel_id}> ด้วย /channel register ก่อน"),
+                    Colour::RED,
+                )
+                .await;
+            }
+            Err(e) => {
+                return show_subscription_info_ui(
+                    command,
+                    ctx,
+                    "เกิดข้อผิดพลาด",
+                    &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                    Colour::RED,
+                )
+                .await;
+            }
+        }
+    }
+
+    let subscription = GuildSubscription::new(
+        guild_id,
+        manga.id.unwrap(),
+        manga.title.clone(),
+        channel_id.map(|channel_id| channel_id.to_string()),
+    );
+    let target = match channel_id {
+        Some(channel_id) => format!("ช่อง <#{channel_id}>"),
+        None => "ทุกช่องที่ลงทะเบียนไว้".to_string(),
+    };
+
+    match GuildSubscriptionService::subscribe(&subscription).await {
+        Ok(true) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "ติดตามการ์ตูนสำเร็จ",
+                &format!("จะแจ้งเตือนตอนใหม่ของ **{}** ไปยัง{target}", manga.title),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Ok(false) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "ติดตามอยู่แล้ว",
+                &format!("เซิร์ฟเวอร์นี้ติดตาม **{}** ไปยัง{target}อยู่แล้ว", manga.title),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Err(e) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn unfollow_manga(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let Some(manga) = get_manga_option(ctx, command, "unfollow").await? else {
+        return Ok(());
+    };
+    let channel_id = get_channel_option(command, "unfollow").map(|id| id.to_string());
+
+    match GuildSubscriptionService::unsubscribe(
+        &guild_id,
+        &manga.id.unwrap(),
+        channel_id.as_deref(),
+    )
+    .await
+    {
+        Ok(0) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "ไม่ได้ติดตาม",
+                &format!("เซิร์ฟเวอร์นี้ไม่ได้ติดตาม **{}**", manga.title),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Ok(_) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เลิกติดตามสำเร็จ",
+                &format!("เลิกแจ้งเตือนตอนใหม่ของ **{}** แล้ว", manga.title),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการลบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+// สิทธิ์ที่ role สำหรับรับแจ้งเตือนมีได้ ใครก็กดปุ่มรับ role ได้ จึงต้องไม่มีสิทธิ์ที่มากกว่าสมาชิกทั่วไป
+fn allowed_role_permissions() -> Permissions {
+    Permissions::VIEW_CHANNEL
+        | Permissions::SEND_MESSAGES
+        | Permissions::SEND_MESSAGES_IN_THREADS
+        | Permissions::READ_MESSAGE_HISTORY
+        | Permissions::ADD_REACTIONS
+        | Permissions::EMBED_LINKS
+        | Permissions::ATTACH_FILES
+        | Permissions::USE_EXTERNAL_EMOJIS
+        | Permissions::USE_EXTERNAL_STICKERS
+        | Permissions::USE_APPLICATION_COMMANDS
+        | Permissions::CREATE_PUBLIC_THREADS
+        | Permissions::CREATE_INSTANT_INVITE
+        | Permissions::CHANGE_NICKNAME
+        | Permissions::CONNECT
+        | Permissions::SPEAK
+        | Permissions::STREAM
+        | Permissions::USE_VAD
+}
+
+// ตรวจว่า role นี้ให้สมาชิกรับเองผ่านปุ่มได้อย่างปลอดภัย คืนเหตุผลถ้าไม่ได้
+// ไม่รับ @everyone, role ที่ระบบหรือบอทอื่นจัดการ, role ที่มีสิทธิ์พิเศษ และ role ที่ไม่ต่ำกว่า role สูงสุดของบอท
+async fn check_assignable_role(
+    ctx: &Context,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> serenity::Result<Option<String>> {
+    if role_id.get() == guild_id.get() {
+        return Ok(Some("ไม่สามารถใช้ @everyone ได้".to_string()));
+    }
+
+    let roles = guild_id.roles(&ctx.http).await?;
+    let Some(role) = roles.get(&role_id) else {
+        return Ok(Some("ไม่พบ role นี้ในเซิร์ฟเวอร์".to_string()));
+    };
+    if role.managed {
+        return Ok(Some(
+            "role นี้ถูกจัดการโดยระบบหรือบอทอื่น ให้สมาชิกรับเองไม่ได้".to_string(),
+        ));
+    }
+    let extra = role.permissions - allowed_role_permissions();
+    if !extra.is_empty() {
+        return Ok(Some(format!(
+            "role นี้มีสิทธิ์พิเศษ ({}) ใช้ role ที่ไม่มีสิทธิ์เพิ่มจากสมาชิกทั่วไปแทน",
+            extra.get_permission_names().join(", ")
+        )));
+    }
+
+    let bot_id = ctx.http.get_current_user().await?.id;
+    let bot = ctx.http.get_member(guild_id, bot_id).await?;
+    let bot_position = bot
+        .roles
+        .iter()
+        .filter_map(|id| roles.get(id))
+        .map(|role| role.position)
+        .max()
+        .unwrap_or(0);
+    if role.position >= bot_position {
+        return Ok(Some(
+            "role นี้อยู่สูงกว่าหรือเท่ากับ role สูงสุดของบอท ย้าย role ของบอทให้อยู่สูงกว่าก่อน".to_string(),
+        ));
+    }
+    Ok(None)
+}
+
+async fn link_role(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild: GuildId,
+) -> serenity::Result<()> {
+    let guild_id = guild.to_string();
+    let Some(manga) = get_manga_option(ctx, command, "link-role").await? else {
+        return Ok(());
+    };
+    let Some(role_id) =
+        get_sub_option(command, "link-role", "role").and_then(|value| value.as_role_id())
+    else {
+        return show_subscription_info_ui(command, ctx, "เกิดข้อผิดพลาด", "กรุณาระบุ role", Colour::RED)
+            .await;
+    };
+    // การตรวจ role ต้องเรียก API หลายครั้ง จึง defer ไว้ก่อนเพื่อไม่ให้เกินเวลาตอบกลับ
+    command.defer_ephemeral(&ctx.http).await?;
+    match check_assignable_role(ctx, guild, role_id).await {
+        Ok(None) => {}
+        Ok(Some(reason)) => {
+            return edit_subscription_info_ui(command, ctx, "ใช้ role นี้ไม่ได้", &reason, Colour::RED)
+                .await;
+        }
+        Err(e) => {
+            return edit_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("ตรวจสอบ role ไม่สำเร็จ: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    }
+
+    let role = MangaRole::new(
+        guild_id.clone(),
+        manga.id.unwrap(),
+        manga.title.clone(),
+        role_id.to_string(),
+    );
+    if let Err(e) = MangaRoleService::link(&role).await {
+        return edit_subscription_info_ui(
+            command,
+            ctx,
+            "เกิดข้อผิดพลาด",
+            &format!("เกิดข้อผิดพลาดในการบันทึกข้อมูล: {e}"),
+            Colour::RED,
+        )
+        .await;
+    }
+
+    let mut description = format!(
+        "จะ mention <@&{role_id}> เมื่อ **{}** มีตอนใหม่\n\
+        สมาชิกรับ role ได้จากปุ่มในข้อความแจ้งเตือน (บอทต้องมีสิทธิ์จัดการ role และอยู่สูงกว่า role นี้)",
+        manga.title
+    );
+    let followed = GuildSubscriptionService::get_by_guild(&guild_id)
+        .await
+        .map(|subscriptions| subscriptions.iter().any(|s| Some(s.manga_id) == manga.id))
+        .unwrap_or(true);
+    if !followed {
+        description.push_str("\n\n⚠️ เซิร์ฟเวอร์นี้ยังไม่ได้ติดตามการ์ตูนเรื่องนี้ ใช้ /subscription follow ก่อน");
+    }
+
+    edit_subscription_info_ui(
+        command,
+        ctx,
+        "ผูก role สำเร็จ",
+        &description,
+        Colour::DARK_GREEN,
+    )
+    .await
+}
+
+async fn unlink_role(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+    let Some(manga) = get_manga_option(ctx, command, "unlink-role").await? else {
+        return Ok(());
+    };
+
+    match MangaRoleService::unlink(&guild_id, &manga.id.unwrap()).await {
+        Ok(true) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "ยกเลิกการผูก role สำเร็จ",
+                &format!("จะไม่ mention role เมื่อ **{}** มีตอนใหม่", manga.title),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Ok(false) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "ไม่พบข้อมูล",
+                &format!("**{}** ยังไม่ได้ผูกกับ role ใด", manga.title),
+                Colour::GOLD,
+            )
+            .await
+        }
+        Err(e) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการลบข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+async fn list_subscriptions(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let guild_id = command.guild_id.unwrap().to_string();
+
+    match GuildSubscriptionService::get_by_guild(&guild_id).await {
+        Ok(subscriptions) if subscriptions.is_empty() => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "การ์ตูนที่ติดตาม",
+                "เซิร์ฟเวอร์นี้ยังไม่ได้ติดตามการ์ตูนเรื่องใด ใช้ /subscription follow เพื่อเริ่มติดตาม",
+                Colour::GOLD,
+            )
+            .await
+        }
+        Ok(subscriptions) => {
+            let roles = MangaRoleService::get_by_guild(&guild_id)
+                .await
+                .unwrap_or_default();
+            let description = subscriptions
+                .iter()
+                .map(|subscription| {
+                    let target = match &subscription.channel_id {
+                        Some(channel_id) => format!("<#{channel_id}>"),
+                        None => "ทุกช่อง".to_string(),
+                    };
+                    match roles
+                        .iter()
+                        .find(|role| role.manga_id == subscription.manga_id)
+                    {
+                        Some(role) => format!(
+                            "• **{}** → {target} (mention <@&{}>)",
+                            subscription.manga_title, role.role_id
+                        ),
+                        None => format!("• **{}** → {target}", subscription.manga_title),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            show_subscription_info_ui(command, ctx, "การ์ตูนที่ติดตาม", &description, Colour::BLUE).await
+        }
+        Err(e) => {
+            show_subscription_info_ui(
+                command,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    _: &serenity::prelude::TypeMap,
+) -> serenity::Result<()> {
+    let Some(guild_id) = command.guild_id else {
+        return show_subscription_info_ui(
+            command,
+            ctx,
+            "ใช้ในเซิร์ฟเวอร์เท่านั้น",
+            "คำสั่งนี้ใช้ได้เฉพาะในเซิร์ฟเวอร์",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    let subcommand = command.data.options.first().unwrap();
+    let subcommand_name = &subcommand.name;
+
+    match subcommand_name.as_str() {
+        "follow" => follow_manga(ctx, command).await,
+        "unfollow" => unfollow_manga(ctx, command).await,
+        "link-role" => link_role(ctx, command, guild_id).await,
+        "unlink-role" => unlink_role(ctx, command).await,
+        "list" => list_subscriptions(ctx, command).await,
+        _ => {
+            show_subscription_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await
+        }
+    }
+}
+
+// ตอบกลับการกดปุ่มเฉพาะผู้กด
+async fn show_component_info_ui(
+    component: &ComponentInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบติดตามการ์ตูน"));
+
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
+// ตอบกลับการกดปุ่มที่ defer ไว้แล้ว
+async fn edit_component_info_ui(
+    component: &ComponentInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    color: Colour,
+) -> serenity::Result<()> {
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(color)
+        .footer(CreateEmbedFooter::new("ระบบติดตามการ์ตูน"));
+
+    component
+        .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+// ปุ่มรับหรือคืน role ของมังงะในข้อความแจ้งเตือน (value เป็นรหัสมังงะ)
+async fn toggle_manga_role(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    value: &str,
+) -> serenity::Result<()> {
+    let (Some(guild_id), Some(member), Ok(manga_id)) = (
+        component.guild_id,
+        component.member.as_ref(),
+        ObjectId::parse_str(value),
+    ) else {
+        return show_component_info_ui(
+            component,
+            ctx,
+            "เกิดข้อผิดพลาด",
+            "ข้อมูลของปุ่มไม่ถูกต้อง",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    let role = match MangaRoleService::get(&guild_id.to_string(), &manga_id).await {
+        Ok(Some(role)) => role,
+        Ok(None) => {
+            return show_component_info_ui(
+                component,
+                ctx,
+                "ไม่พบข้อมูล",
+                "การ์ตูนเรื่องนี้ไม่ได้ผูกกับ role แล้ว",
+                Colour::GOLD,
+            )
+            .await;
+        }
+        Err(e) => {
+            return show_component_info_ui(
+                component,
+                ctx,
+                "เกิดข้อผิดพลาด",
+                &format!("เกิดข้อผิดพลาดในการดึงข้อมูล: {e}"),
+                Colour::RED,
+            )
+            .await;
+        }
+    };
+    let Ok(role_id) = role.role_id.parse::<u64>().map(RoleId::new) else {
+        return show_component_info_ui(
+            component,
+            ctx,
+            "เกิดข้อผิดพลาด",
+            "รหัส role ไม่ถูกต้อง",
+            Colour::RED,
+        )
+        .await;
+    };
+
+    // การตรวจ role และการเปลี่ยน role ต้องเรียก API หลายครั้ง จึง defer ไว้ก่อน
+    component.defer_ephemeral(&ctx.http).await?;
+
+    // ตรวจ role อีกครั้งก่อนให้ เพราะสิทธิ์ของ role อาจถูกแก้หลังจากผูกไว้แล้ว
+    let has_role = member.roles.contains(&role_id);
+    if !has_role {
+        let reason = match check_assignable_role(ctx, guild_id, role_id).await {
+            Ok(reason) => reason,
+            Err(e) => Some(format!("ตรวจสอบ role ไม่สำเร็จ: {e}")),
+        };
+        if let Some(reason) = reason {
+            return edit_component_info_ui(
+                component,
+                ctx,
+                "ไม่สามารถให้ role ได้",
+                &reason,
+                Colour::RED,
+            )
+            .await;
+        }
+    }
+    let result = if has_role {
+        ctx.http
+            .remove_member_role(guild_id, member.user.id, role_id, Some("เลิกรับแจ้งเตือนการ์ตูน"))
+            .await
+    } else {
+        ctx.http
+            .add_member_role(guild_id, member.user.id, role_id, Some("รับแจ้งเตือนการ์ตูน"))
+            .await
+    };
+
+    match result {
+        Ok(_) if has_role => {
+            edit_component_info_ui(
+                component,
+                ctx,
+                "เลิกรับแจ้งเตือนแล้ว",
+                &format!("นำ role <@&{role_id}> ของ **{}** ออกแล้ว", role.manga_title),
+                Colour::BLUE,
+            )
+            .await
+        }
+        Ok(_) => {
+            edit_component_info_ui(
+                component,
+                ctx,
+                "รับแจ้งเตือนแล้ว",
+                &format!(
+                    "ได้รับ role <@&{role_id}> แล้ว จะถูก mention เมื่อ **{}** มีตอนใหม่ กดอีกครั้งเพื่อเลิกรับ",
+                    role.manga_title
+                ),
+                Colour::DARK_GREEN,
+            )
+            .await
+        }
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการเปลี่ยน role ของผู้ใช้: {e:?}");
+            edit_component_info_ui(
+                component,
+                ctx,
+                "ไม่สามารถเปลี่ยน role ได้",
+                "บอทไม่มีสิทธิ์จัดการ role นี้ กรุณาแจ้งผู้ดูแลเซิร์ฟเวอร์",
+                Colour::RED,
+            )
+            .await
+        }
+    }
+}
+
+// จัดการปุ่มของคำสั่ง subscription (custom_id รูปแบบ subscription_<action>:<ค่า>)
+pub async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> serenity::Result<()> {
+    let custom_id = component.data.custom_id.as_str();
+    let (action, value) = custom_id.split_once(':').unwrap_or((custom_id, ""));
+
+    match action {
+        "subscription_role" => toggle_manga_role(ctx, component, value).await,
+        _ => Ok(()),
+    }
+}
@@ -1,22 +1,22 @@
+use crate::service::reading_progress_service::ReadingProgressService;
 use crate::service::user_service::UserService;
-use chrono;
+use crate::service::user_subscription_service::UserSubscriptionService;
+use crate::utils::time::discord_timestamp;
 use serenity::all::{
     Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
     CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
 };
 
+// จำนวนการ์ตูนที่ติดตามและที่กำลังอ่านสูงสุดที่แสดงในโปรไฟล์
+const MAX_PROFILE_SUBSCRIPTIONS: usize = 10;
+
 pub fn register() -> CreateCommand {
     CreateCommand::new("user")
         .description("จัดการข้อมูลผู้ใช้")
         .add_option(CreateCommandOption::new(
             CommandOptionType::SubCommand,
-            "info",
-            "ดูข้อมูลของผู้ใช้",
-        ))
-        .add_option(CreateCommandOption::new(
-            CommandOptionType::SubCommand,
-            "register",
-            "ลงทะเบียนผู้ใช้",
+            "profile",
+            "ดูโปรไฟล์ การ์ตูนที่ติดตาม และการตั้งค่าการแจ้งเตือน",
         ))
         .add_option(CreateCommandOption::new(
             CommandOptionType::SubCommand,
@@ -51,143 +51,195 @@ pub async fn show_user_info_ui(
         .await
 }
 
-// Modified register_user function
-async fn register_user(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
-    let guild_id: String = command.guild_id.unwrap().to_string();
+// ลงทะเบียนผู้ใช้อัตโนมัติเมื่อใช้คำสั่งในเซิร์ฟเวอร์ครั้งแรก
+pub async fn ensure_user_registered(ctx: &Context, command: &CommandInteraction) {
+    let Some(guild_id) = command.guild_id else {
+        return;
+    };
     let user_id: String = command.user.id.to_string();
-    let user_name: String = command.user.name.clone();
+
+    match UserService::check_user_exists(&user_id, &guild_id.to_string()).await {
+        Ok(true) => return,
+        Ok(false) => {}
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการตรวจสอบข้อมูลผู้ใช้: {e:?}");
+            return;
+        }
+    }
+
+    let guild_name: String = match guild_id.to_partial_guild(&ctx.http).await {
+        Ok(guild) => guild.name,
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการดึงข้อมูลเซิร์ฟเวอร์: {e:?}");
+            return;
+        }
+    };
+    let guild_user_nickname: String = command
+        .member
+        .as_ref()
+        .and_then(|member| member.nick.clone())
+        .unwrap_or_else(|| command.user.name.clone());
     let global_name: String = command.user.global_name.clone().unwrap_or_default();
 
-    let guild: serenity::all::PartialGuild = command
-        .guild_id
-        .unwrap()
-        .to_partial_guild(&ctx.http)
-        .await
-        .unwrap();
-    let guild_name: String = guild.name.clone();
+    if let Err(e) = UserService::register_user(
+        &guild_id.to_string(),
+        &user_id,
+        &guild_name,
+        &guild_user_nickname,
+        &global_name,
+        &command.user.name,
+    )
+    .await
+    {
+        println!("เกิดข้อผิดพลาดในการลงทะเบียนผู้ใช้: {e:?}");
+    }
+}
 
-    let member: serenity::all::Member = command
-        .guild_id
-        .unwrap()
-        .member(&ctx.http, command.user.id)
-        .await
-        .unwrap();
-    let guild_user_nickname: &String = member.nick.as_ref().unwrap_or(&member.user.name);
+// โปรไฟล์ของผู้ใช้ ข้อมูลผู้ใช้ในเซิร์ฟเวอร์ การ์ตูนที่ติดตาม และการตั้งค่าการแจ้งเตือน
+async fn get_user_profile(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    let user_id: String = command.user.id.to_string();
 
-    match UserService::check_user_exists(&user_id, &guild_id).await {
-        Ok(true) => {
-            show_user_info_ui(
-                command,
-                ctx,
-                "การลงทะเบียน",
-                &format!("ผู้ใช้ {} ได้ลงทะเบียนไว้แล้ว", guild_user_nickname),
-                Colour::GOLD,
-            )
-            .await
-        }
-        Ok(false) => {
-            match UserService::register_user(
-                &guild_id,
-                &user_id,
-                &guild_name,
-                &guild_user_nickname,
-                &global_name,
-                &user_name,
-            )
-            .await
-            {
-                Ok(_) => {
-                    show_user_info_ui(
-                        command,
-                        ctx,
-                        "การลงทะเบียนสำเร็จ",
-                        &format!("ลงทะเบียนผู้ใช้ {} สำเร็จ", guild_user_nickname),
-                        Colour::DARK_GREEN,
-                    )
-                    .await
-                }
-                Err(e) => {
-                    show_user_info_ui(
-                        command,
-                        ctx,
-                        "เกิดข้อผิดพลาด",
-                        &format!("เกิดข้อผิดพลาด: {}", e),
-                        Colour::RED,
-                    )
-                    .await
-                }
-            }
-        }
-        Err(e) => {
-            show_user_info_ui(
+    let user = match command.guild_id {
+        Some(guild_id) => UserService::find_by_user_id(&user_id, &guild_id.to_string()).await,
+        None => Ok(None),
+    };
+    let subscriptions = UserSubscriptionService::get_by_user(&user_id).await;
+
+    let (user, subscriptions) = match (user, subscriptions) {
+        (Ok(user), Ok(subscriptions)) => (user, subscriptions),
+        (Err(e), _) | (_, Err(e)) => {
+            return show_user_info_ui(
                 command,
                 ctx,
                 "เกิดข้อผิดพลาด",
-                &format!("เกิดข้อผิดพลาดในการตรวจสอบข้อมูลผู้ใช้: {}", e),
+                &format!("เกิดข้อผิดพลาด: {e}"),
                 Colour::RED,
             )
-            .await
+            .await;
         }
-    }
-}
+    };
 
-// Modified get_user_info function
-async fn get_user_info(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
-    let user_id: String = command.user.id.to_string();
-    let guild_id: String = command.guild_id.unwrap().to_string();
+    let mut embed: CreateEmbed = CreateEmbed::new()
+        .title(format!(
+            "โปรไฟล์ของ {}",
+            command
+                .user
+                .global_name
+                .as_ref()
+                .unwrap_or(&command.user.name)
+        ))
+        .thumbnail(command.user.face())
+        .color(Colour::BLUE)
+        .footer(CreateEmbedFooter::new("ระบบจัดการผู้ใช้"));
 
-    match UserService::find_by_user_id(&user_id, &guild_id).await {
-        Ok(Some(user)) => {
-            let description = format!(
+    if let Some(user) = &user {
+        embed = embed.field(
+            "ข้อมูลผู้ใช้",
+            format!(
                 "**User:** {}\n\
-                **ชื่อผู้ใช้:** {}\n\
                 **ชื่อในเซิร์ฟเวอร์:** {}\n\
                 **เซิร์ฟเวอร์:** {}\n\
                 **วันที่ลงทะเบียน:** {}",
                 user.user_name,
-                user.global_name,
                 user.guild_user_nickname,
                 user.guild_name,
-                chrono::DateTime::<chrono::Utc>::from(user.created_at.to_system_time())
-                    .format("%d/%m/%Y %H:%M:%S")
-                    .to_string()
-            );
+                discord_timestamp(&user.created_at)
+            ),
+            false,
+        );
+    }
 
-            show_user_info_ui(command, ctx, "ข้อมูลผู้ใช้", &description, Colour::BLUE).await
+    let subscribed = if subscriptions.is_empty() {
+        "ยังไม่ได้ติดตามการ์ตูนทาง DM".to_string()
+    } else {
+        let mut lines: Vec<String> = subscriptions
+            .iter()
+            .take(MAX_PROFILE_SUBSCRIPTIONS)
+            .map(|subscription| format!("• {}", subscription.manga_title))
+            .collect();
+        if subscriptions.len() > MAX_PROFILE_SUBSCRIPTIONS {
+            lines.push(format!(
+                "และอีก {} เรื่อง (ดูทั้งหมดด้วย /dm list)",
+                subscriptions.len() - MAX_PROFILE_SUBSCRIPTIONS
+            ));
         }
-        Ok(None) => {
-            show_user_info_ui(command, ctx, "ไม่พบข้อมูล", "ไม่พบข้อมูลผู้ใช้ในระบบ", Colour::RED).await
-        }
-        Err(e) => {
-            show_user_info_ui(
-                command,
-                ctx,
-                "เกิดข้อผิดพลาด",
-                &format!("เกิดข้อผิดพลาด: {}", e),
-                Colour::RED,
-            )
-            .await
+        lines.join("\n")
+    };
+    embed = embed.field(
+        format!("การ์ตูนที่ติดตามทาง DM ({})", subscriptions.len()),
+        subscribed,
+        false,
+    );
+
+    match ReadingProgressService::get_by_user(&user_id).await {
+        Ok(progress) => {
+            let reading = if progress.is_empty() {
+                "ยังไม่มีประวัติการอ่าน".to_string()
+            } else {
+                let mut lines: Vec<String> = progress
+                    .iter()
+                    .take(MAX_PROFILE_SUBSCRIPTIONS)
+                    .map(|progress| {
+                        format!(
+                            "• {} (ตอนที่ {})",
+                            progress.manga_title, progress.last_read_chapter
+                        )
+                    })
+                    .collect();
+                lines.push("ดูเรื่องที่ยังอ่านไม่ทันด้วย /manga unread".to_string());
+                lines.join("\n")
+            };
+            embed = embed.field(
+                format!("ความคืบหน้าการอ่าน ({})", progress.len()),
+                reading,
+                false,
+            );
         }
+        Err(e) => println!("เกิดข้อผิดพลาดในการดึงความคืบหน้าการอ่าน: {e:?}"),
     }
+
+    let dm_status = if subscriptions.is_empty() {
+        "-"
+    } else if subscriptions.iter().any(|s| s.dm_closed_at.is_some()) {
+        "⚠️ หยุดส่งชั่วคราว บอทส่ง DM หาคุณไม่ได้"
+    } else {
+        "✅ เปิดรับแจ้งเตือน"
+    };
+    embed = embed.field("การแจ้งเตือนทาง DM", dm_status, true);
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await
 }
 
 // Modified update_user function
 async fn update_user(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
-    let guild_id: String = command.guild_id.unwrap().to_string();
+    let Some(guild_id) = command.guild_id else {
+        return show_user_info_ui(
+            command,
+            ctx,
+            "ใช้ในเซิร์ฟเวอร์เท่านั้น",
+            "ข้อมูลผู้ใช้บันทึกแยกตามเซิร์ฟเวอร์ กรุณาใช้คำสั่งนี้ในเซิร์ฟเวอร์",
+            Colour::RED,
+        )
+        .await;
+    };
+    let guild_id: String = guild_id.to_string();
     let user_id: String = command.user.id.to_string();
 
-    // ดึงข้อมูลล่าสุดจาก Discord
-    let member: serenity::all::Member = command
-        .guild_id
-        .unwrap()
-        .member(&ctx.http, command.user.id)
-        .await
-        .unwrap();
-    let guild_user_nickname: String = member
-        .nick
+    // ใช้ข้อมูลสมาชิกที่ Discord ส่งมากับคำสั่ง ไม่ต้องเรียก API เพิ่ม
+    let guild_user_nickname: String = command
+        .member
         .as_ref()
-        .unwrap_or(&member.user.name)
-        .to_string();
+        .and_then(|member| member.nick.clone())
+        .unwrap_or_else(|| command.user.name.clone());
     let global_name: String = command.user.global_name.clone().unwrap_or_default();
     let user_name: String = command.user.name.clone();
 
@@ -213,9 +265,7 @@ async fn update_user(ctx: &Context, command: &CommandInteraction) -> serenity::R
                         user.global_name,
                         user.guild_user_nickname,
                         user.guild_name,
-                        chrono::DateTime::<chrono::Utc>::from(user.updated_at.to_system_time())
-                            .format("%d/%m/%Y %H:%M:%S")
-                            .to_string()
+                        discord_timestamp(&user.updated_at)
                     );
 
                     show_user_info_ui(
@@ -232,7 +282,7 @@ async fn update_user(ctx: &Context, command: &CommandInteraction) -> serenity::R
                         command,
                         ctx,
                         "เกิดข้อผิดพลาด",
-                        &format!("เกิดข้อผิดพลาดในการอัพเดทข้อมูล: {}", e),
+                        &format!("เกิดข้อผิดพลาดในการอัพเดทข้อมูล: {e}"),
                         Colour::RED,
                     )
                     .await
@@ -240,21 +290,14 @@ async fn update_user(ctx: &Context, command: &CommandInteraction) -> serenity::R
             }
         }
         Ok(None) => {
-            show_user_info_ui(
-                command,
-                ctx,
-                "ไม่พบข้อมูล",
-                "ไม่พบข้อมูลผู้ใช้ในระบบ กรุณาลงทะเบียนก่อน",
-                Colour::RED,
-            )
-            .await
+            show_user_info_ui(command, ctx, "ไม่พบข้อมูล", "ไม่พบข้อมูลผู้ใช้ในระบบ", Colour::RED).await
         }
         Err(e) => {
             show_user_info_ui(
                 command,
                 ctx,
                 "เกิดข้อผิดพลาด",
-                &format!("เกิดข้อผิดพลาดในการค้นหาข้อมูลผู้ใช้: {}", e),
+                &format!("เกิดข้อผิดพลาดในการค้นหาข้อมูลผู้ใช้: {e}"),
                 Colour::RED,
             )
             .await
@@ -272,8 +315,7 @@ pub async fn run(
     let subcommand_name: &String = &subcommand.name;
 
     match subcommand_name.as_str() {
-        "info" => get_user_info(ctx, command).await,
-        "register" => register_user(ctx, command).await,
+        "profile" => get_user_profile(ctx, command).await,
         "update" => update_user(ctx, command).await,
         _ => show_user_info_ui(command, ctx, "ไม่รู้จักคำสั่ง", "ไม่รู้จักคำสั่งย่อยนี้", Colour::RED).await,
     }
@@ -0,0 +1,397 @@
+use crate::models::channels::{Channel, ChannelLayout, ChannelWebhook};
+use crate::models::manga::Manga;
+use crate::models::manga_thread::MangaThread;
+use crate::service::channels_service::ChannelsService;
+use crate::service::manga_thread_service::MangaThreadService;
+use crate::sources;
+use crate::utils::secret;
+use mongodb::bson::oid::ObjectId;
+use reqwest::Url;
+use serenity::all::{
+    AutoArchiveDuration, ChannelId, ChannelType, Context, CreateActionRow, CreateAllowedMentions,
+    CreateEmbed, CreateForumPost, CreateMessage, CreateThread, CreateWebhook, EditThread,
+    ExecuteWebhook, Message, WebhookId,
+};
+use serenity::builder::Builder;
+use serenity::http::HttpError;
+
+// รหัสข้อผิดพลาดของ Discord เมื่อช่องหรือเธรดถูกลบไปแล้ว
+const UNKNOWN_CHANNEL_ERROR_CODE: isize = 10003;
+// รหัสข้อผิดพลาดของ Discord เมื่อ webhook ถูกลบไปแล้ว
+const UNKNOWN_WEBHOOK_ERROR_CODE: isize = 10015;
+// ความยาวสูงสุดของชื่อเธรดที่ Discord รองรับ
+const MAX_THREAD_NAME_LENGTH: usize = 100;
+// ความยาวสูงสุดของชื่อผู้ส่งผ่าน webhook ที่ Discord รองรับ
+const MAX_WEBHOOK_USERNAME_LENGTH: usize = 80;
+// ชื่อของ webhook ที่บอทสร้างในช่อง
+const WEBHOOK_NAME: &str = "Manga Updates";
+
+// ข้อความที่จะส่ง แยกส่วนไว้เพื่อส่งเป็นข้อความของบอทหรือผ่าน webhook ได้
+#[derive(Clone)]
+pub struct OutgoingMessage {
+    content: Option<String>,
+    embeds: Vec<CreateEmbed>,
+    components: Vec<CreateActionRow>,
+    allowed_mentions: CreateAllowedMentions,
+}
+
+impl OutgoingMessage {
+    pub fn new(embed: CreateEmbed) -> Self {
+        OutgoingMessage {
+            content: None,
+            embeds: vec![embed],
+            components: Vec::new(),
+            allowed_mentions: CreateAllowedMentions::new(),
+        }
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn components(mut self, components: Vec<CreateActionRow>) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn allowed_mentions(mut self, allowed_mentions: CreateAllowedMentions) -> Self {
+        self.allowed_mentions = allowed_mentions;
+        self
+    }
+
+    pub fn to_message(&self) -> CreateMessage {
+        let message = CreateMessage::new()
+            .embeds(self.embeds.clone())
+            .components(self.components.clone())
+            .allowed_mentions(self.allowed_mentions.clone());
+        match &self.content {
+            Some(content) => message.content(content),
+            None => message,
+        }
+    }
+
+    fn to_webhook(&self, branding: &Branding) -> ExecuteWebhook {
+        let username: String = branding
+            .username
+            .chars()
+            .take(MAX_WEBHOOK_USERNAME_LENGTH)
+            .collect();
+        let mut webhook = ExecuteWebhook::new()
+            .username(username)
+            .embeds(self.embeds.clone())
+            .components(self.components.clone())
+            .allowed_mentions(self.allowed_mentions.clone());
+        if let Some(content) = &self.content {
+            webhook = webhook.content(content);
+        }
+        if let Some(avatar_url) = &branding.avatar_url {
+            webhook = webhook.avatar_url(avatar_url);
+        }
+        webhook
+    }
+}
+
+// ชื่อและรูปที่แสดงเป็นผู้ส่งเมื่อส่งผ่าน webhook
+pub struct Branding {
+    pub username: String,
+    pub avatar_url: Option<String>,
+}
+
+impl Branding {
+    // ใช้ชื่อเรื่องคู่กับชื่อเว็บไซต์ต้นทางเป็นชื่อผู้ส่ง และปกของเรื่องเป็นรูปผู้ส่ง
+    pub fn for_manga(manga: &Manga) -> Self {
+        let username = match sources::registry().find_by_url(&manga.url) {
+            Some(source) => format!("{} • {}", manga.title, source.name()),
+            None => manga.title.clone(),
+        };
+        Branding {
+            username,
+            avatar_url: manga.image_url.clone(),
+        }
+    }
+}
+
+fn is_error_code(error: &serenity::Error, code: isize) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == code
+    )
+}
+
+fn parse_channel_id(channel: &Channel) -> Option<ChannelId> {
+    channel.channel_id.parse::<u64>().ok().map(ChannelId::new)
+}
+
+const INVALID_CHANNEL_ID: serenity::Error = serenity::Error::Other("รหัสช่องไม่ถูกต้อง");
+const INVALID_WEBHOOK: serenity::Error =
+    serenity::Error::Other("ข้อมูล webhook ไม่ถูกต้องหรือถอดรหัส token ไม่ได้");
+const SECRET_KEY_MISSING: serenity::Error =
+    serenity::Error::Other("ยังไม่ได้ตั้งค่า SECRET_KEY สำหรับเข้ารหัส token ของ webhook");
+
+// สร้าง webhook ใหม่ในช่องแล้วบันทึกรหัสกับ token ที่เข้ารหัสแล้วไว้
+pub async fn create_webhook(ctx: &Context, channel: &Channel) -> serenity::Result<ChannelWebhook> {
+    if !secret::is_configured() {
+        return Err(SECRET_KEY_MISSING);
+    }
+    let webhook = parse_channel_id(channel)
+        .ok_or(INVALID_CHANNEL_ID)?
+        .create_webhook(&ctx.http, CreateWebhook::new(WEBHOOK_NAME))
+        .await?;
+    let url = Url::parse(&webhook.url()?).map_err(|_| INVALID_WEBHOOK)?;
+    let (webhook_id, token) = serenity::utils::parse_webhook(&url).ok_or(INVALID_WEBHOOK)?;
+    let channel_webhook = ChannelWebhook {
+        webhook_id: webhook_id.to_string(),
+        sealed_token: secret::seal(token).ok_or(INVALID_WEBHOOK)?,
+    };
+
+    if let Err(e) = ChannelsService::set_webhook(
+        &channel.guild_id,
+        &channel.channel_id,
+        Some(&channel_webhook),
+    )
+    .await
+    {
+        println!(
+            "เกิดข้อผิดพลาดในการบันทึก webhook ของช่อง {}: {e:?}",
+            channel.channel_id
+        );
+    }
+    Ok(channel_webhook)
+}
+
+// webhook ที่ใช้แทนอันที่ถูกลบไป ถ้ามีการสร้างใหม่ไว้แล้ว (ข้อมูลช่องที่ถืออยู่อาจเก่า) จะใช้อันนั้น
+// เพื่อไม่ให้สร้าง webhook ซ้ำหลายอันในรอบเดียวกัน
+async fn replace_webhook(
+    ctx: &Context,
+    channel: &Channel,
+    deleted: &ChannelWebhook,
+) -> serenity::Result<ChannelWebhook> {
+    let saved = ChannelsService::get_channel(&channel.guild_id, &channel.channel_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|channel| channel.webhook);
+    match saved {
+        Some(saved) if saved != *deleted => Ok(saved),
+        _ => create_webhook(ctx, channel).await,
+    }
+}
+
+// รหัสและ token ของ webhook สำหรับเรียก API ถอดรหัส token เฉพาะตอนใช้งาน
+fn webhook_credentials(webhook: &ChannelWebhook) -> Option<(WebhookId, String)> {
+    let webhook_id = webhook.webhook_id.parse::<u64>().ok().map(WebhookId::new)?;
+    let token = secret::open(&webhook.sealed_token)?;
+    Some((webhook_id, token))
+}
+
+// ลบ webhook ที่บอทสร้างไว้ ใช้ตอนเลิกใช้ webhook หรือลบช่องออกจากระบบ
+pub async fn delete_webhook(ctx: &Context, webhook: &ChannelWebhook) -> serenity::Result<()> {
+    let (webhook_id, token) = webhook_credentials(webhook).ok_or(INVALID_WEBHOOK)?;
+    match ctx
+        .http
+        .delete_webhook_with_token(webhook_id, &token, None)
+        .await
+    {
+        Err(e) if is_error_code(&e, UNKNOWN_WEBHOOK_ERROR_CODE) => Ok(()),
+        result => result,
+    }
+}
+
+async fn execute_webhook(
+    ctx: &Context,
+    webhook: &ChannelWebhook,
+    builder: ExecuteWebhook,
+) -> serenity::Result<Message> {
+    let (webhook_id, token) = webhook_credentials(webhook).ok_or(INVALID_WEBHOOK)?;
+    builder
+        .execute(&ctx.http, (webhook_id, token.as_str(), true))
+        .await?
+        .ok_or(serenity::Error::Other("webhook ไม่ได้คืนข้อความ"))
+}
+
+// ส่งผ่าน webhook ของช่อง ถ้า webhook ถูกลบไปแล้วจะสร้างใหม่และส่งอีกครั้ง
+async fn send_with_webhook(
+    ctx: &Context,
+    channel: &Channel,
+    webhook: &ChannelWebhook,
+    builder: ExecuteWebhook,
+) -> serenity::Result<Message> {
+    match execute_webhook(ctx, webhook, builder.clone()).await {
+        Err(e) if is_error_code(&e, UNKNOWN_WEBHOOK_ERROR_CODE) => {
+            println!("webhook ของช่อง {} ถูกลบไปแล้ว สร้างใหม่", channel.channel_id);
+            let webhook = replace_webhook(ctx, channel, webhook).await?;
+            execute_webhook(ctx, &webhook, builder).await
+        }
+        result => result,
+    }
+}
+
+// ส่งข้อความเข้าช่องหรือเธรด ช่องที่เปิดใช้ webhook จะส่งผ่าน webhook ก่อน
+// ถ้าส่งผ่าน webhook ไม่สำเร็จจะให้บอทส่งแทน
+async fn send_to(
+    ctx: &Context,
+    channel: &Channel,
+    thread_id: Option<ChannelId>,
+    message: &OutgoingMessage,
+    branding: &Branding,
+) -> serenity::Result<()> {
+    if let Some(webhook) = &channel.webhook {
+        let mut builder = message.to_webhook(branding);
+        if let Some(thread_id) = thread_id {
+            builder = builder.in_thread(thread_id);
+        }
+        match send_with_webhook(ctx, channel, webhook, builder).await {
+            Ok(_) => return Ok(()),
+            Err(e) => println!(
+                "ส่งผ่าน webhook ของช่อง {} ไม่สำเร็จ ใช้บอทส่งแทน: {e:?}",
+                channel.channel_id
+            ),
+        }
+    }
+
+    let target = match thread_id {
+        Some(thread_id) => thread_id,
+        None => parse_channel_id(channel).ok_or(INVALID_CHANNEL_ID)?,
+    };
+    target.send_message(&ctx.http, message.to_message()).await?;
+    Ok(())
+}
+
+// สร้างโพสต์ใหม่ในฟอรัมโดยใช้ข้อความอัพเดทเป็นข้อความแรก คืนรหัสเธรดของโพสต์
+async fn create_forum_post(
+    ctx: &Context,
+    channel: &Channel,
+    thread_name: String,
+    message: &OutgoingMessage,
+    branding: &Branding,
+) -> serenity::Result<ChannelId> {
+    if let Some(webhook) = &channel.webhook {
+        let builder = message
+            .to_webhook(branding)
+            .thread_name(thread_name.clone());
+        match send_with_webhook(ctx, channel, webhook, builder).await {
+            Ok(sent) => return Ok(sent.channel_id),
+            Err(e) => println!(
+                "สร้างโพสต์ผ่าน webhook ของช่อง {} ไม่สำเร็จ ใช้บอทสร้างแทน: {e:?}",
+                channel.channel_id
+            ),
+        }
+    }
+
+    let thread = parse_channel_id(channel)
+        .ok_or(INVALID_CHANNEL_ID)?
+        .create_forum_post(
+            &ctx.http,
+            CreateForumPost::new(thread_name, message.to_message()),
+        )
+        .await?;
+    Ok(thread.id)
+}
+
+// เธรดที่บันทึกไว้ของมังงะเรื่องนี้ ถ้ายังใช้ได้
+// เธรดที่ถูกเก็บถาวรอัตโนมัติจะเปิดกลับมาใช้ต่อ เพื่อไม่ให้มีเธรดซ้ำของเรื่องเดียวกัน
+// เธรดที่ถูกลบ ถูกล็อก หรือเปิดกลับมาไม่ได้จะคืน None เพื่อให้สร้างเธรดใหม่
+async fn find_open_thread(
+    ctx: &Context,
+    channel: &Channel,
+    manga_id: &ObjectId,
+) -> serenity::Result<Option<ChannelId>> {
+    let saved = MangaThreadService::get(&channel.channel_id, manga_id)
+        .await
+        .map_err(|_| serenity::Error::Other("ดึงข้อมูลเธรดไม่สำเร็จ"))?;
+    let Some(thread_id) = saved
+        .and_then(|thread| thread.thread_id.parse::<u64>().ok())
+        .map(ChannelId::new)
+    else {
+        return Ok(None);
+    };
+
+    match thread_id.to_channel(&ctx.http).await {
+        Ok(serenity::model::channel::Channel::Guild(thread)) => {
+            let Some(metadata) = thread.thread_metadata else {
+                return Ok(Some(thread_id));
+            };
+            if metadata.locked {
+                return Ok(None);
+            }
+            if !metadata.archived {
+                return Ok(Some(thread_id));
+            }
+            match thread_id
+                .edit_thread(&ctx.http, EditThread::new().archived(false))
+                .await
+            {
+                Ok(_) => Ok(Some(thread_id)),
+                Err(e) => {
+                    println!("เปิดเธรด {thread_id} ที่ถูกเก็บถาวรไม่สำเร็จ สร้างเธรดใหม่แทน: {e:?}");
+                    Ok(None)
+                }
+            }
+        }
+        Ok(_) => Ok(None),
+        Err(e) if is_error_code(&e, UNKNOWN_CHANNEL_ERROR_CODE) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// ส่งข้อความที่ไม่ได้เป็นของมังงะเรื่องใดเรื่องหนึ่ง เช่น สรุปรวม ไปยังช่องที่ลงทะเบียนไว้
+pub async fn send_channel_message(
+    ctx: &Context,
+    channel: &Channel,
+    message: &OutgoingMessage,
+    branding: &Branding,
+) -> serenity::Result<()> {
+    send_to(ctx, channel, None, message, branding).await
+}
+
+// ส่งข้อความของมังงะหนึ่งเรื่องไปยังช่องที่ลงทะเบียนไว้
+// ช่องที่แยกเธรดตามเรื่องจะส่งเข้าเธรดของเรื่องนั้น และสร้างเธรดใหม่เมื่อยังไม่มีหรือใช้ไม่ได้แล้ว
+pub async fn send_manga_message(
+    ctx: &Context,
+    channel: &Channel,
+    manga_id: &ObjectId,
+    manga_title: &str,
+    message: &OutgoingMessage,
+    branding: &Branding,
+) -> serenity::Result<()> {
+    if !channel.layout.per_manga() {
+        return send_to(ctx, channel, None, message, branding).await;
+    }
+
+    if let Some(thread_id) = find_open_thread(ctx, channel, manga_id).await? {
+        return send_to(ctx, channel, Some(thread_id), message, branding).await;
+    }
+
+    let thread_name: String = manga_title.chars().take(MAX_THREAD_NAME_LENGTH).collect();
+    let thread_id = match channel.layout {
+        ChannelLayout::Forum => {
+            create_forum_post(ctx, channel, thread_name, message, branding).await?
+        }
+        _ => {
+            let thread = parse_channel_id(channel)
+                .ok_or(INVALID_CHANNEL_ID)?
+                .create_thread(
+                    &ctx.http,
+                    CreateThread::new(thread_name)
+                        .kind(ChannelType::PublicThread)
+                        .auto_archive_duration(AutoArchiveDuration::OneWeek),
+                )
+                .await?;
+            send_to(ctx, channel, Some(thread.id), message, branding).await?;
+            thread.id
+        }
+    };
+
+    let manga_thread = MangaThread::new(
+        channel.guild_id.clone(),
+        channel.channel_id.clone(),
+        *manga_id,
+        thread_id.to_string(),
+    );
+    if let Err(e) = MangaThreadService::save(&manga_thread).await {
+        println!("เกิดข้อผิดพลาดในการบันทึกเธรดของ {manga_title}: {e:?}");
+    }
+    Ok(())
+}
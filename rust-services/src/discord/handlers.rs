@@ -1,56 +1,118 @@
 use crate::discord::commands;
 use crate::discord::commands::auto_manga_commands::AutoMangaCommands;
+use crate::discord::commands::digest_commands::DigestCommands;
 use serenity::all::{
-    Command, Context, CreateInteractionResponse, CreateInteractionResponseMessage, EventHandler,
-    Interaction, Ready,
+    Command, CommandInteraction, ComponentInteraction, Context, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EventHandler, Interaction, Ready,
 };
 use serenity::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Ready เกิดซ้ำทุกครั้งที่เชื่อมต่อ gateway ใหม่ ใช้กันไม่ให้เริ่มงานเบื้องหลังซ้ำ
+static BACKGROUND_TASKS_STARTED: AtomicBool = AtomicBool::new(false);
 
 pub struct Handlers;
 
-#[async_trait]
-impl EventHandler for Handlers {
-    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        if let Interaction::Command(command) = interaction {
-            let data_read: tokio::sync::RwLockReadGuard<'_, serenity::prelude::TypeMap> =
-                ctx.data.read().await;
-            let result: Result<(), serenity::Error> = match command.data.name.as_str() {
-                // "user" => commands::user_commands::run(&ctx, &command, &data_read).await,
-                "manga" => commands::manga_commands::run(&ctx, &command, &data_read).await,
-                "channel" => commands::channels_commands::run(&ctx, &command, &data_read).await,
-                _ => {
-                    // ไม่พบคำสั่ง
-                    command
-                        .create_response(
-                            &ctx.http,
-                            CreateInteractionResponse::Message(
-                                CreateInteractionResponseMessage::new()
-                                    .content("ไม่พบคำสั่งนี้")
-                                    .ephemeral(true),
-                            ),
-                        )
-                        .await
-                        .ok();
-                    Ok(())
-                }
-            };
-
-            // จัดการข้อผิดพลาด
-            if let Err(why) = result {
-                println!("เกิดข้อผิดพลาดในคำสั่ง '{}': {:?}", command.data.name, why);
-
-                // ส่งข้อความแจ้งข้อผิดพลาด
-                let _ = command
+impl Handlers {
+    async fn handle_command(ctx: &Context, command: CommandInteraction) {
+        // ลงทะเบียนผู้ใช้ที่ใช้คำสั่งครั้งแรกแบบเบื้องหลัง เพื่อไม่ให้คำสั่งตอบช้า
+        let (register_ctx, register_command) = (ctx.clone(), command.clone());
+        tokio::spawn(async move {
+            commands::user_commands::ensure_user_registered(&register_ctx, &register_command).await;
+        });
+
+        let data_read: tokio::sync::RwLockReadGuard<'_, serenity::prelude::TypeMap> =
+            ctx.data.read().await;
+        let result: Result<(), serenity::Error> = match command.data.name.as_str() {
+            "user" => commands::user_commands::run(ctx, &command, &data_read).await,
+            "manga" => commands::manga_commands::run(ctx, &command, &data_read).await,
+            "channel" => commands::channels_commands::run(ctx, &command, &data_read).await,
+            "source" => commands::source_commands::run(ctx, &command, &data_read).await,
+            "dm" => commands::dm_commands::run(ctx, &command, &data_read).await,
+            "subscription" => commands::subscription_commands::run(ctx, &command, &data_read).await,
+            "settings" => commands::settings_commands::run(ctx, &command, &data_read).await,
+            _ => {
+                // ไม่พบคำสั่ง
+                command
                     .create_response(
                         &ctx.http,
                         CreateInteractionResponse::Message(
                             CreateInteractionResponseMessage::new()
-                                .content("เกิดข้อผิดพลาดในการประมวลผลคำสั่ง")
+                                .content("ไม่พบคำสั่งนี้")
                                 .ephemeral(true),
                         ),
                     )
-                    .await;
+                    .await
+                    .ok();
+                Ok(())
+            }
+        };
+
+        // จัดการข้อผิดพลาด
+        if let Err(why) = result {
+            println!("เกิดข้อผิดพลาดในคำสั่ง '{}': {:?}", command.data.name, why);
+
+            // ส่งข้อความแจ้งข้อผิดพลาด
+            let _ = command
+                .create_response(
+                    &ctx.http,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content("เกิดข้อผิดพลาดในการประมวลผลคำสั่ง")
+                            .ephemeral(true),
+                    ),
+                )
+                .await;
+        }
+    }
+
+    // ตอบ autocomplete ตามคำสั่งที่ผู้ใช้กำลังพิมพ์
+    async fn handle_autocomplete(ctx: &Context, interaction: CommandInteraction) {
+        let result: Result<(), serenity::Error> = match interaction.data.name.as_str() {
+            "manga" | "subscription" | "dm" => {
+                commands::manga_commands::autocomplete_manga(ctx, &interaction).await
+            }
+            "settings" => {
+                commands::settings_commands::autocomplete_timezone(ctx, &interaction).await
+            }
+            _ => Ok(()),
+        };
+
+        if let Err(why) = result {
+            println!(
+                "เกิดข้อผิดพลาดใน autocomplete ของคำสั่ง '{}': {:?}",
+                interaction.data.name, why
+            );
+        }
+    }
+
+    // ปุ่มและเมนูต่าง ๆ เลือกคำสั่งที่รับผิดชอบจาก prefix ของ custom_id
+    async fn handle_component(ctx: &Context, component: ComponentInteraction) {
+        let custom_id = component.data.custom_id.as_str();
+        let result: Result<(), serenity::Error> = if custom_id.starts_with("manga_") {
+            commands::manga_commands::handle_component(ctx, &component).await
+        } else if custom_id.starts_with("subscription_") {
+            commands::subscription_commands::handle_component(ctx, &component).await
+        } else {
+            Ok(())
+        };
+
+        if let Err(why) = result {
+            println!("เกิดข้อผิดพลาดในการจัดการปุ่ม '{custom_id}': {why:?}");
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for Handlers {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) => Self::handle_command(&ctx, command).await,
+            Interaction::Autocomplete(interaction) => {
+                Self::handle_autocomplete(&ctx, interaction).await
             }
+            Interaction::Component(component) => Self::handle_component(&ctx, component).await,
+            _ => {}
         }
     }
 
@@ -63,7 +125,11 @@ impl EventHandler for Handlers {
         // ลงทะเบียนคำสั่งทั้งหมดแบบ global
         match Command::set_global_commands(&ctx.http, commands).await {
             Ok(_) => println!("ลงทะเบียนคำสั่งทั้งหมดสำเร็จ"),
-            Err(why) => println!("ลงทะเบียนคำสั่งล้มเหลว: {:?}", why),
+            Err(why) => println!("ลงทะเบียนคำสั่งล้มเหลว: {why:?}"),
+        }
+
+        if BACKGROUND_TASKS_STARTED.swap(true, Ordering::SeqCst) {
+            return;
         }
 
         // เริ่มการอัพเดทอัตโนมัติ
@@ -73,14 +139,26 @@ impl EventHandler for Handlers {
             let auto_manga: AutoMangaCommands = AutoMangaCommands::new();
             auto_manga.run_periodic_update(&ctx_clone).await;
         });
+
+        // เริ่มการส่งสรุปตามรอบของช่องที่ใช้โหมดสรุป
+        let ctx_clone: Context = ctx.clone();
+
+        tokio::spawn(async move {
+            let digest: DigestCommands = DigestCommands::new();
+            digest.run_digest_scheduler(&ctx_clone).await;
+        });
     }
 }
 
 // ฟังก์ชั่นรวบรวมคำสั่งทั้งหมด
 fn collect_all_commands() -> Vec<serenity::all::CreateCommand> {
     vec![
-        // commands::user_commands::register(),
+        commands::user_commands::register(),
         commands::manga_commands::register(),
         commands::channels_commands::register(),
+        commands::source_commands::register(),
+        commands::subscription_commands::register(),
+        commands::dm_commands::register(),
+        commands::settings_commands::register(),
     ]
 }
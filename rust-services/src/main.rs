@@ -1,8 +1,21 @@
 mod discord;
 mod models;
 mod service;
+mod sources;
 mod utils;
 use crate::discord::client;
+use crate::service::channels_service::ChannelsService;
+use crate::service::chapter_service::ChapterService;
+use crate::service::guild_settings_service::GuildSettingsService;
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_role_service::MangaRoleService;
+use crate::service::manga_service::MangaService;
+use crate::service::manga_thread_service::MangaThreadService;
+use crate::service::page_cache_service::PageCacheService;
+use crate::service::pending_notification_service::PendingNotificationService;
+use crate::service::reading_progress_service::ReadingProgressService;
+use crate::service::user_service::UserService;
+use crate::service::user_subscription_service::UserSubscriptionService;
 use crate::utils::mongo;
 use dotenv::dotenv;
 use std::error::Error;
@@ -11,6 +24,27 @@ use std::error::Error;
 async fn main() -> Result<(), Box<dyn Error>> {
     dotenv().ok();
     mongo::init().await?;
+    let merged = MangaService::merge_duplicate_urls().await?;
+    if merged > 0 {
+        println!("รวมมังงะที่ URL ซ้ำกัน {merged} เรื่อง");
+    }
+    let seeded = GuildSubscriptionService::seed_existing_guilds().await?;
+    if seeded > 0 {
+        println!("เพิ่มการติดตามมังงะให้เซิร์ฟเวอร์ที่ลงทะเบียนไว้เดิม {seeded} รายการ");
+    }
+    MangaService::create_indexes().await?;
+    ChapterService::create_indexes().await?;
+    ChannelsService::create_indexes().await?;
+    GuildSubscriptionService::create_indexes().await?;
+    UserSubscriptionService::create_indexes().await?;
+    UserService::create_indexes().await?;
+    ReadingProgressService::create_indexes().await?;
+    MangaRoleService::create_indexes().await?;
+    PendingNotificationService::create_indexes().await?;
+    GuildSettingsService::create_indexes().await?;
+    MangaThreadService::create_indexes().await?;
+    PageCacheService::create_indexes().await?;
+    sources::init();
     client::run().await?;
 
     Ok(())
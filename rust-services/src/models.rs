@@ -1,2 +1,14 @@
 pub mod channels;
+pub mod chapter;
+pub mod chapter_number;
+pub mod guild_settings;
+pub mod guild_subscription;
 pub mod manga;
+pub mod manga_role;
+pub mod manga_thread;
+pub mod page_cache;
+pub mod pending_notification;
+pub mod reading_progress;
+pub mod release_pattern;
+pub mod user;
+pub mod user_subscription;
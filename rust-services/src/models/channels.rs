@@ -1,7 +1,119 @@
+use crate::models::manga::Manga;
+use crate::utils::time::WEEKDAY_NAMES;
+use chrono::{Datelike, Duration, TimeZone, Timelike, Utc};
 use mongodb::bson::{doc, oid::ObjectId};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug)]
+// วิธีส่งการแจ้งเตือนของช่อง ส่งทันที หรือรวบรวมไว้แล้วส่งเป็นสรุปตามรอบ (ตามเขตเวลาของเซิร์ฟเวอร์)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DeliveryMode {
+    #[default]
+    Immediate,
+    Hourly,
+    Daily {
+        hour: u32,
+    },
+    // weekday: 0 = วันจันทร์ ถึง 6 = วันอาทิตย์
+    Weekly {
+        weekday: u32,
+        hour: u32,
+    },
+}
+
+impl DeliveryMode {
+    // รอบส่งสรุปล่าสุดที่ไม่เกินเวลาที่กำหนด นับชั่วโมงและวันตามเขตเวลาของ now
+    // คืน None สำหรับการส่งทันที
+    pub fn last_slot<Tz: TimeZone>(
+        &self,
+        now: chrono::DateTime<Tz>,
+    ) -> Option<chrono::DateTime<Utc>> {
+        let start_of_hour = now.with_minute(0)?.with_second(0)?.with_nanosecond(0)?;
+
+        let slot = match *self {
+            DeliveryMode::Immediate => return None,
+            DeliveryMode::Hourly => start_of_hour,
+            DeliveryMode::Daily { hour } => {
+                let slot = start_of_hour - Duration::hours(now.hour() as i64 - hour as i64);
+                if slot > now {
+                    slot - Duration::days(1)
+                } else {
+                    slot
+                }
+            }
+            DeliveryMode::Weekly { weekday, hour } => {
+                let days_since =
+                    (now.weekday().num_days_from_monday() as i64 - weekday as i64).rem_euclid(7);
+                let slot = start_of_hour
+                    - Duration::hours(now.hour() as i64 - hour as i64)
+                    - Duration::days(days_since);
+                if slot > now {
+                    slot - Duration::weeks(1)
+                } else {
+                    slot
+                }
+            }
+        };
+        Some(slot.with_timezone(&Utc))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            DeliveryMode::Immediate => "ส่งทันที".to_string(),
+            DeliveryMode::Hourly => "สรุปทุกชั่วโมง".to_string(),
+            DeliveryMode::Daily { hour } => format!("สรุปทุกวัน เวลา {hour:02}:00"),
+            DeliveryMode::Weekly { weekday, hour } => format!(
+                "สรุปทุก{} เวลา {hour:02}:00",
+                WEEKDAY_NAMES.get(*weekday as usize).unwrap_or(&"?")
+            ),
+        }
+    }
+}
+
+// รูปแบบการโพสต์ในช่อง โพสต์ลงช่องโดยตรง หรือแยกเธรด/โพสต์ฟอรัมของแต่ละเรื่อง
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelLayout {
+    #[default]
+    Channel,
+    Thread,
+    Forum,
+}
+
+impl ChannelLayout {
+    pub fn per_manga(&self) -> bool {
+        *self != ChannelLayout::Channel
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ChannelLayout::Channel => "โพสต์ลงช่อง",
+            ChannelLayout::Thread => "แยกเธรดตามเรื่อง",
+            ChannelLayout::Forum => "แยกโพสต์ฟอรัมตามเรื่อง",
+        }
+    }
+}
+
+// webhook ที่บอทสร้างไว้ในช่อง เก็บรหัสกับ token แยกกัน
+// token ใช้ส่งข้อความในนามของ webhook ได้ จึงเข้ารหัสด้วย utils::secret ก่อนบันทึกและไม่แสดงใน log
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChannelWebhook {
+    pub webhook_id: String,
+    pub sealed_token: String,
+}
+
+impl fmt::Debug for ChannelWebhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelWebhook")
+            .field("webhook_id", &self.webhook_id)
+            .field("sealed_token", &"<ซ่อน>")
+            .finish()
+    }
+}
+
+// ช่องสำหรับส่งการแจ้งเตือน หนึ่งเซิร์ฟเวอร์มีได้หลายช่อง (unique ตาม guild_id + channel_id)
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Channel {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
@@ -9,6 +121,22 @@ pub struct Channel {
     pub guild_id: String,
     pub guild_name: String,
     pub channel_name: String,
+    // คำอธิบายว่าช่องนี้ใช้ทำอะไร เช่น "มังงะแอคชั่น"
+    #[serde(default)]
+    pub purpose: Option<String>,
+    // ส่งเฉพาะมังงะที่ชื่อเรื่องหรือชื่ออื่นมีคำนี้ ถ้าไม่กำหนดจะส่งทุกเรื่อง
+    #[serde(default)]
+    pub title_filter: Option<String>,
+    #[serde(default)]
+    pub delivery_mode: DeliveryMode,
+    #[serde(default)]
+    pub layout: ChannelLayout,
+    // ส่งผ่าน webhook ที่บอทจัดการเอง ถ้าไม่ได้เปิดใช้จะเป็น None
+    #[serde(default)]
+    pub webhook: Option<ChannelWebhook>,
+    // เวลาที่ส่งสรุปล่าสุด ใช้กับโหมดสรุปตามรอบ
+    #[serde(default)]
+    pub last_digest_at: Option<mongodb::bson::DateTime>,
     pub created_at: mongodb::bson::DateTime,
     pub updated_at: mongodb::bson::DateTime,
 }
@@ -28,8 +156,90 @@ impl Channel {
             guild_id,
             guild_name,
             channel_name,
+            purpose: None,
+            title_filter: None,
+            delivery_mode: DeliveryMode::Immediate,
+            layout: ChannelLayout::Channel,
+            webhook: None,
+            last_digest_at: None,
             created_at: now,
             updated_at: now,
         }
     }
+
+    // ตรวจว่ามังงะเรื่องนี้ผ่านตัวกรองของช่องหรือไม่
+    pub fn accepts(&self, manga: &Manga) -> bool {
+        let Some(filter) = &self.title_filter else {
+            return true;
+        };
+        let filter = filter.to_lowercase();
+        std::iter::once(&manga.title)
+            .chain(manga.alt_titles.iter())
+            .any(|title| title.to_lowercase().contains(&filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Asia::Bangkok;
+
+    fn utc(day: u32, hour: u32) -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    // 2024-01-10 เป็นวันพุธ เวลาไทย 10:30 (03:30 UTC)
+    fn wednesday_morning() -> chrono::DateTime<chrono_tz::Tz> {
+        Bangkok.with_ymd_and_hms(2024, 1, 10, 10, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn immediate_and_hourly_slots() {
+        let now = wednesday_morning();
+        assert_eq!(DeliveryMode::Immediate.last_slot(now), None);
+        assert_eq!(DeliveryMode::Hourly.last_slot(now), Some(utc(10, 3)));
+    }
+
+    #[test]
+    fn daily_slot_uses_today_or_yesterday() {
+        let now = wednesday_morning();
+        assert_eq!(
+            DeliveryMode::Daily { hour: 9 }.last_slot(now),
+            Some(utc(10, 2))
+        );
+        assert_eq!(
+            DeliveryMode::Daily { hour: 11 }.last_slot(now),
+            Some(utc(9, 4))
+        );
+
+        let on_the_hour = Bangkok.with_ymd_and_hms(2024, 1, 10, 10, 0, 0).unwrap();
+        assert_eq!(
+            DeliveryMode::Daily { hour: 10 }.last_slot(on_the_hour),
+            Some(utc(10, 3))
+        );
+    }
+
+    #[test]
+    fn weekly_slot_goes_back_to_the_configured_weekday() {
+        let now = wednesday_morning();
+        let weekly = |weekday, hour| DeliveryMode::Weekly { weekday, hour }.last_slot(now);
+        assert_eq!(weekly(2, 9), Some(utc(10, 2)));
+        assert_eq!(weekly(2, 11), Some(utc(3, 4)));
+        assert_eq!(weekly(0, 8), Some(utc(8, 1)));
+        assert_eq!(weekly(6, 20), Some(utc(7, 13)));
+    }
+
+    #[test]
+    fn slot_is_counted_in_the_timezone_of_now() {
+        // 20:00 UTC ของวันที่ 9 คือ 03:00 ของวันที่ 10 ตามเวลาไทย
+        let now = utc(9, 20).with_timezone(&Bangkok);
+        assert_eq!(
+            DeliveryMode::Daily { hour: 1 }.last_slot(now),
+            Some(utc(9, 18))
+        );
+        assert_eq!(
+            DeliveryMode::Daily { hour: 1 }.last_slot(utc(9, 20)),
+            Some(utc(9, 1))
+        );
+    }
 }
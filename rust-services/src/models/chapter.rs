@@ -0,0 +1,46 @@
+use crate::models::chapter_number::ChapterNumber;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// ประวัติของตอนที่ระบบเคยตรวจพบ หนึ่งเอกสารต่อหนึ่งตอน
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Chapter {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    pub number: ChapterNumber,
+    // ChapterNumber::sort_key ของ number ใช้เรียงและนับตอนใน MongoDB
+    pub number_key: String,
+    pub title: String,
+    pub url: String,
+    pub release_date: Option<String>,
+    // รอบการดึงข้อมูลที่พบตอนนี้เป็นครั้งแรก
+    pub scrape_run_id: ObjectId,
+    pub first_seen_at: DateTime,
+}
+
+impl Chapter {
+    pub fn new(
+        manga_id: ObjectId,
+        manga_title: String,
+        number: ChapterNumber,
+        title: String,
+        url: String,
+        release_date: Option<String>,
+        scrape_run_id: ObjectId,
+    ) -> Self {
+        Chapter {
+            id: None,
+            manga_id,
+            manga_title,
+            number_key: number.sort_key(),
+            number,
+            title,
+            url,
+            release_date,
+            scrape_run_id,
+            first_seen_at: DateTime::from(std::time::SystemTime::now()),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use mongodb::bson::Bson;
+use regex::Regex;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::OnceLock;
+
+// หมายเลขตอนที่รองรับทศนิยม (10.5) ตอนย่อย (10-2) และตอนพิเศษ (10 Extra, Special)
+// การเรียงลำดับ: ตอนที่ไม่มีเลข < 10 < 10 Extra < 10-1 < 10.5 < 11
+// บันทึกลง MongoDB เป็น string และอ่านค่าเดิมที่เป็นตัวเลข (i32) ได้
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChapterNumber {
+    whole: Option<u32>,
+    // ตัวเลขหลังจุดทศนิยมแบบตัดเลข 0 ท้ายออก เทียบแบบ string แล้วได้ลำดับเดียวกับทศนิยม
+    fraction: String,
+    part: Option<u32>,
+    label: Option<String>,
+}
+
+// เลขตอน: เลขหลัก ทศนิยม และเลขตอนย่อย
+// เลขตอนย่อยมีได้ไม่เกิน 3 หลัก เพื่อไม่ให้ปีของวันที่ เช่น "7 - 2024/01/02" ถูกนับเป็นตอนย่อย
+const NUMBER_PATTERN: &str = r"(\d+)(?:\.(\d+))?(?:\s*(?:-|_|part|pt\.?|ส่วนที่)\s*(\d{1,3})\b)?";
+
+fn number_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(&format!("(?i){NUMBER_PATTERN}")).unwrap())
+}
+
+// เลขตอนที่อยู่หลังคำว่าตอน เช่น "ตอนที่ 12", "Chapter 10.5", "Ch. 3", "Ep 7"
+fn keyword_number_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)(?:ตอนที่|ตอน|\bchapter|\bchap\.?|\bch\.?|\bepisode|\bep\.?)\s*#?\s*{NUMBER_PATTERN}"
+        ))
+        .unwrap()
+    })
+}
+
+fn label_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(extra|special|side\s*story|omake|bonus|prologue|epilogue|oneshot|one-shot)\b|ตอนพิเศษ|พิเศษ",
+        )
+        .unwrap()
+    })
+}
+
+impl ChapterNumber {
+    pub fn from_whole(whole: u32) -> Self {
+        ChapterNumber {
+            whole: Some(whole),
+            fraction: String::new(),
+            part: None,
+            label: None,
+        }
+    }
+
+    // แปลงข้อความจากหน้าเว็บ เช่น "Chapter 10.5", "ตอนที่ 12 ส่วนที่ 2", "Vol. 3 Ch. 25", "Extra"
+    // คืน None เมื่อข้อความว่าง
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        // ใช้เลขที่อยู่หลังคำว่าตอนก่อน เพื่อไม่ให้เลขเล่มหรือเลขซีซั่น เช่น "Vol. 3 Ch. 25" ถูกใช้เป็นเลขตอน
+        // ถ้าไม่มีคำว่าตอนใช้เลขตัวแรก เพราะวันที่ที่ต่อท้าย เช่น "25 March 12, 2024" มักอยู่หลังเลขตอน
+        let number = keyword_number_regex()
+            .captures(text)
+            .or_else(|| number_regex().captures(text));
+        let label = label_regex().find(text);
+
+        // ตอนพิเศษที่คำระบุอยู่ก่อนตัวเลข เช่น "Extra 3" ไม่ใช่ตอนที่ 3 จึงเก็บทั้งข้อความเป็นชื่อ
+        let label_first = match (&number, &label) {
+            (Some(number), Some(label)) => label.start() < number.get(0).unwrap().start(),
+            (None, _) => true,
+            _ => false,
+        };
+
+        if label_first {
+            let label_text = match label {
+                Some(label) => text[label.start()..].trim().to_string(),
+                None => text.to_string(),
+            };
+            return Some(ChapterNumber {
+                whole: None,
+                fraction: String::new(),
+                part: None,
+                label: Some(label_text),
+            });
+        }
+
+        let number = number?;
+        Some(ChapterNumber {
+            whole: number[1].parse().ok(),
+            fraction: number
+                .get(2)
+                .map(|m| m.as_str().trim_end_matches('0').to_string())
+                .unwrap_or_default(),
+            part: number.get(3).and_then(|m| m.as_str().parse().ok()),
+            label: label.map(|m| m.as_str().to_string()),
+        })
+    }
+}
+
+impl ChapterNumber {
+    // ข้อความที่เรียงแบบ string แล้วได้ลำดับเดียวกับ Ord ใช้เรียงและเทียบตอนในฐานข้อมูล
+    // แต่ละส่วนขึ้นต้นด้วย 0 (ไม่มีค่า) หรือ 1 (มีค่า) และเว้นวรรคหลังทศนิยมซึ่งน้อยกว่าตัวเลขทุกตัว
+    pub fn sort_key(&self) -> String {
+        let whole = match self.whole {
+            Some(whole) => format!("1{whole:010}"),
+            None => "0".to_string(),
+        };
+        let part = match self.part {
+            Some(part) => format!("1{part:010}"),
+            None => "0".to_string(),
+        };
+        let label = match &self.label {
+            Some(label) => format!("1{label}"),
+            None => "0".to_string(),
+        };
+        format!("{whole}{} {part}{label}", self.fraction)
+    }
+}
+
+impl fmt::Display for ChapterNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(whole) = self.whole else {
+            return write!(f, "{}", self.label.as_deref().unwrap_or("?"));
+        };
+
+        write!(f, "{whole}")?;
+        if !self.fraction.is_empty() {
+            write!(f, ".{}", self.fraction)?;
+        }
+        if let Some(part) = self.part {
+            write!(f, "-{part}")?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, " {label}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for ChapterNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+struct ChapterNumberVisitor;
+
+impl Visitor<'_> for ChapterNumberVisitor {
+    type Value = ChapterNumber;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("หมายเลขตอนแบบตัวเลขหรือข้อความ")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<ChapterNumber, E> {
+        Ok(ChapterNumber::from_whole(value.max(0) as u32))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<ChapterNumber, E> {
+        Ok(ChapterNumber::from_whole(value as u32))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<ChapterNumber, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<ChapterNumber, E> {
+        ChapterNumber::parse(value).ok_or_else(|| E::custom("หมายเลขตอนว่าง"))
+    }
+}
+
+impl<'de> Deserialize<'de> for ChapterNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ChapterNumberVisitor)
+    }
+}
+
+impl From<&ChapterNumber> for Bson {
+    fn from(number: &ChapterNumber) -> Self {
+        Bson::String(number.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson;
+
+    fn parse(text: &str) -> ChapterNumber {
+        ChapterNumber::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_number_after_chapter_keyword() {
+        assert_eq!(parse("Ch. 10.5").to_string(), "10.5");
+        assert_eq!(parse("Chapter 12 Part 2").to_string(), "12-2");
+        assert_eq!(parse("ตอนที่ 12 ส่วนที่ 2").to_string(), "12-2");
+        assert_eq!(parse("Vol. 3 Ch. 25").to_string(), "25");
+        assert_eq!(parse("Season 2 Episode 5").to_string(), "5");
+    }
+
+    #[test]
+    fn ignores_release_date_next_to_number() {
+        assert_eq!(parse("25 March 12, 2024").to_string(), "25");
+        assert_eq!(parse("Chapter 7 - 2024/01/02").to_string(), "7");
+        assert_eq!(parse("2024-01-02 ตอนที่ 8").to_string(), "8");
+    }
+
+    #[test]
+    fn parses_special_chapters() {
+        assert_eq!(parse("Extra").to_string(), "Extra");
+        assert_eq!(parse("Extra 3").to_string(), "Extra 3");
+        assert_eq!(parse("Chapter 10 Extra").to_string(), "10 Extra");
+        assert_eq!(ChapterNumber::parse("  "), None);
+    }
+
+    #[test]
+    fn orders_chapters() {
+        let ordered: Vec<ChapterNumber> = ["Extra", "10", "10 Extra", "10-1", "10.5", "11"]
+            .iter()
+            .map(|text| parse(text))
+            .collect();
+        assert!(ordered.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn sort_key_agrees_with_ord() {
+        let numbers: Vec<ChapterNumber> = [
+            "Extra",
+            "Special 2",
+            "9",
+            "10",
+            "10 Extra",
+            "10-1",
+            "10-2",
+            "10.05",
+            "10.5",
+            "10.55",
+            "10.5-1",
+            "11",
+            "100",
+        ]
+        .iter()
+        .map(|text| parse(text))
+        .collect();
+        for a in &numbers {
+            for b in &numbers {
+                assert_eq!(a.cmp(b), a.sort_key().cmp(&b.sort_key()), "{a} กับ {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn deserializes_from_numbers_and_strings() {
+        let from_int: ChapterNumber = bson::from_bson(bson::Bson::Int64(12)).unwrap();
+        let from_double: ChapterNumber = bson::from_bson(bson::Bson::Double(10.5)).unwrap();
+        let from_str: ChapterNumber = bson::from_bson(bson::Bson::String("10.5-2".into())).unwrap();
+        assert_eq!(from_int, ChapterNumber::from_whole(12));
+        assert_eq!(from_double, parse("10.5"));
+        assert_eq!(from_str, parse("10.5-2"));
+        assert_eq!(
+            bson::to_bson(&from_str).unwrap(),
+            bson::Bson::String("10.5-2".into())
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::utils::time;
+use chrono::{Timelike, Utc};
+use chrono_tz::Tz;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TIMEZONE: &str = "Asia/Bangkok";
+pub const DEFAULT_LOCALE: &str = "th";
+
+// ช่วงเวลางดแจ้งเตือนตามเขตเวลาของเซิร์ฟเวอร์ นับตั้งแต่ start_hour ถึงก่อน end_hour
+// ข้ามเที่ยงคืนได้ เช่น 23 ถึง 7
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl QuietHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+// การตั้งค่าของเซิร์ฟเวอร์ unique ตาม guild_id เซิร์ฟเวอร์ที่ยังไม่ได้ตั้งค่าจะใช้ค่าเริ่มต้นจาก new()
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildSettings {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: String,
+    // ชื่อเขตเวลาแบบ IANA เช่น "Asia/Bangkok"
+    pub timezone: String,
+    pub locale: String,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl GuildSettings {
+    pub fn new(guild_id: String) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        GuildSettings {
+            id: None,
+            guild_id,
+            timezone: DEFAULT_TIMEZONE.to_string(),
+            locale: DEFAULT_LOCALE.to_string(),
+            quiet_hours: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // เขตเวลาของเซิร์ฟเวอร์ ถ้าค่าที่บันทึกไว้ไม่ถูกต้องจะใช้เขตเวลาเริ่มต้น
+    pub fn tz(&self) -> Tz {
+        time::parse_timezone(&self.timezone).unwrap_or(chrono_tz::Asia::Bangkok)
+    }
+
+    // อยู่ในช่วงงดแจ้งเตือนหรือไม่ ณ เวลาที่กำหนด
+    pub fn is_quiet_at(&self, now: chrono::DateTime<Utc>) -> bool {
+        self.quiet_hours
+            .as_ref()
+            .is_some_and(|quiet| quiet.contains(now.with_timezone(&self.tz()).hour()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet(start_hour: u32, end_hour: u32) -> QuietHours {
+        QuietHours {
+            start_hour,
+            end_hour,
+        }
+    }
+
+    #[test]
+    fn contains_hours_within_the_same_day() {
+        let hours = quiet(1, 7);
+        assert!(!hours.contains(0));
+        assert!(hours.contains(1));
+        assert!(hours.contains(6));
+        assert!(!hours.contains(7));
+    }
+
+    #[test]
+    fn contains_hours_across_midnight() {
+        let hours = quiet(23, 7);
+        assert!(hours.contains(23));
+        assert!(hours.contains(0));
+        assert!(hours.contains(6));
+        assert!(!hours.contains(7));
+        assert!(!hours.contains(22));
+    }
+
+    #[test]
+    fn same_start_and_end_is_never_quiet() {
+        let hours = quiet(5, 5);
+        assert!((0..24).all(|hour| !hours.contains(hour)));
+    }
+}
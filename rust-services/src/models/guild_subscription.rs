@@ -0,0 +1,37 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// การติดตามมังงะของเซิร์ฟเวอร์ การอัพเดทจะถูกส่งเฉพาะเซิร์ฟเวอร์ที่ติดตามเรื่องนั้น
+// unique ตาม guild_id + manga_id + channel_id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildSubscription {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: String,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    // ช่องที่ต้องการให้ส่ง ถ้าไม่กำหนดจะส่งไปทุกช่องที่ลงทะเบียนไว้ของเซิร์ฟเวอร์
+    pub channel_id: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl GuildSubscription {
+    pub fn new(
+        guild_id: String,
+        manga_id: ObjectId,
+        manga_title: String,
+        channel_id: Option<String>,
+    ) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        GuildSubscription {
+            id: None,
+            guild_id,
+            manga_id,
+            manga_title,
+            channel_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
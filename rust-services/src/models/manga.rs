@@ -1,15 +1,54 @@
+use crate::models::chapter_number::ChapterNumber;
+use crate::utils::url::canonicalize;
 use mongodb::bson::{doc, oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
+// ระยะห่างการเช็คอัพเดท (วินาที) ของเรื่องที่ออกตอนใหม่บ่อย ปกติ และเรื่องที่ไม่มีตอนใหม่นานแล้ว
+pub const HOT_CHECK_INTERVAL: i64 = 60 * 60;
+pub const DEFAULT_CHECK_INTERVAL: i64 = 4 * 60 * 60;
+pub const DORMANT_CHECK_INTERVAL: i64 = 24 * 60 * 60;
+// ตอนใหม่ล่าสุดไม่เกินช่วงนี้ถือว่าเป็นเรื่องที่ออกบ่อย เกินช่วงหลังถือว่าหยุดออกแล้ว (วินาที)
+const HOT_RELEASE_WINDOW: i64 = 14 * 24 * 60 * 60;
+const DORMANT_RELEASE_WINDOW: i64 = 60 * 24 * 60 * 60;
+
+fn default_check_interval() -> i64 {
+    DEFAULT_CHECK_INTERVAL
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Manga {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub title: String,
+    // ชื่ออื่นของเรื่อง ใช้ค้นหาใน autocomplete
+    #[serde(default)]
+    pub alt_titles: Vec<String>,
     pub url: String,
-    pub latest_chapter: i32,
+    // URL ในรูปแบบมาตรฐาน ใช้ค้นหาและกันการเพิ่มเรื่องเดียวกันซ้ำ (unique index)
+    #[serde(default)]
+    pub canonical_url: String,
+    pub latest_chapter: ChapterNumber,
     pub latest_chapter_url: String,
     pub image_url: Option<String>,
+    // เวลาที่ดึงข้อมูลจากเว็บไซต์สำเร็จล่าสุด
+    #[serde(default)]
+    pub last_checked_at: Option<DateTime>,
+    // เวลาที่ต้องเช็คอัพเดทครั้งถัดไป ตัวตั้งเวลาจะดึงเฉพาะเรื่องที่ถึงเวลาแล้ว
+    #[serde(default)]
+    pub next_check_at: Option<DateTime>,
+    // ระยะห่างการเช็คอัพเดทของเรื่องนี้ (วินาที)
+    #[serde(default = "default_check_interval")]
+    pub check_interval: i64,
+    // จำนวนครั้งที่เช็คไม่สำเร็จติดต่อกัน เวลาที่เริ่มเช็คไม่สำเร็จ และข้อผิดพลาดล่าสุด (ล้างเมื่อเช็คสำเร็จ)
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub failing_since: Option<DateTime>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    // แจ้งเตือนผู้ดูแลเรื่องการเช็คไม่สำเร็จครั้งนี้ไปแล้ว
+    #[serde(default)]
+    pub failure_alerted: bool,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -19,7 +58,7 @@ impl Manga {
     pub fn new(
         title: String,
         url: String,
-        latest_chapter: i32,
+        latest_chapter: ChapterNumber,
         latest_chapter_url: String,
         image_url: Option<String>,
     ) -> Self {
@@ -27,12 +66,46 @@ impl Manga {
         Manga {
             id: None,
             title,
+            alt_titles: Vec::new(),
+            canonical_url: canonicalize(&url).unwrap_or_else(|| url.clone()),
             url,
             latest_chapter,
             latest_chapter_url,
             image_url,
+            last_checked_at: Some(now),
+            next_check_at: Some(DateTime::from_millis(
+                now.timestamp_millis() + DEFAULT_CHECK_INTERVAL * 1000,
+            )),
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            consecutive_failures: 0,
+            failing_since: None,
+            last_error: None,
+            failure_alerted: false,
             created_at: now,
             updated_at: now,
         }
     }
+
+    // ระยะห่างการเช็คอัพเดทตามเวลาที่พบตอนใหม่ล่าสุด เรื่องที่ยังไม่มีประวัติจะใช้ค่าปกติ
+    pub fn check_interval_for(last_release: Option<DateTime>, now: DateTime) -> i64 {
+        let Some(last_release) = last_release else {
+            return DEFAULT_CHECK_INTERVAL;
+        };
+        let since_release = (now.timestamp_millis() - last_release.timestamp_millis()) / 1000;
+        if since_release <= HOT_RELEASE_WINDOW {
+            HOT_CHECK_INTERVAL
+        } else if since_release <= DORMANT_RELEASE_WINDOW {
+            DEFAULT_CHECK_INTERVAL
+        } else {
+            DORMANT_CHECK_INTERVAL
+        }
+    }
+
+    // ระยะรอก่อนเช็คใหม่หลังเช็คไม่สำเร็จ (วินาที) เพิ่มเท่าตัวทุกครั้งที่ไม่สำเร็จติดต่อกัน
+    pub fn failure_backoff(consecutive_failures: u32, check_interval: i64) -> i64 {
+        let doublings = consecutive_failures.saturating_sub(1).min(16);
+        check_interval
+            .saturating_mul(1 << doublings)
+            .min(DORMANT_CHECK_INTERVAL)
+    }
 }
@@ -0,0 +1,30 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// role ของเซิร์ฟเวอร์ที่ผูกกับมังงะ จะถูก mention เมื่อมีตอนใหม่ unique ตาม guild_id + manga_id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MangaRole {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: String,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    pub role_id: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl MangaRole {
+    pub fn new(guild_id: String, manga_id: ObjectId, manga_title: String, role_id: String) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        MangaRole {
+            id: None,
+            guild_id,
+            manga_id,
+            manga_title,
+            role_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// เธรดหรือโพสต์ฟอรัมของมังงะหนึ่งเรื่องในช่องที่แยกเธรดตามเรื่อง unique ตาม channel_id + manga_id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MangaThread {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: String,
+    // ช่องหลักที่ลงทะเบียนไว้ (ช่องข้อความหรือฟอรัม)
+    pub channel_id: String,
+    pub manga_id: ObjectId,
+    pub thread_id: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl MangaThread {
+    pub fn new(
+        guild_id: String,
+        channel_id: String,
+        manga_id: ObjectId,
+        thread_id: String,
+    ) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        MangaThread {
+            id: None,
+            guild_id,
+            channel_id,
+            manga_id,
+            thread_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
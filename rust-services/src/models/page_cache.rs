@@ -0,0 +1,17 @@
+use crate::sources::ScrapeResult;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+// หน้าเว็บที่ดาวน์โหลดล่าสุด เก็บเฉพาะ ETag และ Last-Modified สำหรับขอแบบมีเงื่อนไขครั้งถัดไป
+// กับผลที่แปลงแล้ว ซึ่งใช้แทนการแปลงใหม่เมื่อเว็บไซต์ตอบ 304 (ไม่มีการเปลี่ยนแปลง)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedPage {
+    #[serde(rename = "_id")]
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // ลายนิ้วมือของทะเบียนอะแดปเตอร์ที่ใช้แปลง ถ้าตั้งค่าอะแดปเตอร์เปลี่ยนจะไม่ใช้ผลเดิม
+    pub parser: String,
+    pub result: ScrapeResult,
+    pub updated_at: DateTime,
+}
@@ -0,0 +1,39 @@
+use crate::models::chapter_number::ChapterNumber;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// ตอนใหม่ที่รอส่งในสรุปรอบถัดไปของช่องที่ใช้โหมดสรุป หนึ่งเอกสารต่อหนึ่งตอนต่อหนึ่งช่อง
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingNotification {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: String,
+    pub channel_id: String,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    pub chapter_number: ChapterNumber,
+    pub chapter_url: String,
+    pub created_at: DateTime,
+}
+
+impl PendingNotification {
+    pub fn new(
+        guild_id: String,
+        channel_id: String,
+        manga_id: ObjectId,
+        manga_title: String,
+        chapter_number: ChapterNumber,
+        chapter_url: String,
+    ) -> Self {
+        PendingNotification {
+            id: None,
+            guild_id,
+            channel_id,
+            manga_id,
+            manga_title,
+            chapter_number,
+            chapter_url,
+            created_at: DateTime::from(std::time::SystemTime::now()),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::models::chapter_number::ChapterNumber;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// ตอนล่าสุดที่ผู้ใช้อ่านของมังงะแต่ละเรื่อง unique ตาม user_id + manga_id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadingProgress {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: String,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    pub last_read_chapter: ChapterNumber,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ReadingProgress {
+    pub fn new(
+        user_id: String,
+        manga_id: ObjectId,
+        manga_title: String,
+        last_read_chapter: ChapterNumber,
+    ) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        ReadingProgress {
+            id: None,
+            user_id,
+            manga_id,
+            manga_title,
+            last_read_chapter,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
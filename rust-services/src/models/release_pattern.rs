@@ -0,0 +1,303 @@
+use crate::models::manga::{DORMANT_CHECK_INTERVAL, HOT_CHECK_INTERVAL};
+use crate::utils::time::WEEKDAY_NAMES;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+// ระยะห่างการเช็คอัพเดท (วินาที) ช่วงใกล้เวลาที่คาดว่าจะออกตอนใหม่ และช่วงที่ยังห่างจากเวลานั้น
+pub const PEAK_CHECK_INTERVAL: i64 = 15 * 60;
+const MAX_IDLE_CHECK_INTERVAL: i64 = 12 * 60 * 60;
+// เช็คถี่ตั้งแต่ก่อนถึงเวลาที่คาดไว้จนหลังเวลานั้นตามช่วงนี้ (วินาที)
+const RELEASE_WINDOW: i64 = 2 * 60 * 60;
+// หลังรอบที่คาดไว้ผ่านไปโดยไม่มีตอนใหม่ จะเช็คบ่อยขึ้นต่อไปอีกช่วงนี้เผื่อออกช้า (วินาที)
+const LATE_RELEASE_WINDOW: i64 = 24 * 60 * 60;
+// ตอนที่พบห่างกันไม่เกินช่วงนี้นับเป็นการออกตอนครั้งเดียวกัน (วินาที)
+const MERGE_WINDOW: i64 = 6 * 60 * 60;
+// จำนวนครั้งที่ออกตอนขั้นต่ำก่อนจะเชื่อรูปแบบที่เรียนรู้ได้
+const MIN_RELEASES: usize = 4;
+// สัดส่วนขั้นต่ำของการออกตอนที่ตรงกับวันหรือชั่วโมงเดิม ถึงจะนับว่าออกเป็นประจำ
+const MIN_REGULAR_SHARE: f64 = 0.6;
+// ไม่มีตอนใหม่เกินกี่เท่าของระยะห่างปกติถือว่าพักหรือจบแล้ว (อย่างน้อย 30 วัน)
+const HIATUS_GAPS: i64 = 3;
+const MIN_HIATUS: i64 = 30 * 24 * 60 * 60;
+
+// รูปแบบการออกตอนของมังงะ เรียนรู้จากเวลาที่ระบบตรวจพบตอนใหม่ในอดีต
+// วันและชั่วโมงนับตามเวลา UTC
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleasePattern {
+    // ระยะห่างระหว่างการออกตอนแต่ละครั้ง (ค่ามัธยฐาน วินาที)
+    pub typical_gap: i64,
+    // วันที่ออกตอนเป็นประจำ (สำหรับเรื่องที่ออกสัปดาห์ละครั้ง)
+    pub weekday: Option<chrono::Weekday>,
+    // ชั่วโมงที่ออกตอนเป็นประจำ
+    pub hour: Option<u32>,
+    pub last_release: DateTime<Utc>,
+    pub releases: usize,
+}
+
+impl ReleasePattern {
+    // เรียนรู้รูปแบบจากเวลาที่พบตอนใหม่แต่ละครั้ง คืน None ถ้าประวัติยังน้อยเกินไป
+    pub fn learn(release_times: &[DateTime<Utc>]) -> Option<Self> {
+        let mut sorted = release_times.to_vec();
+        sorted.sort();
+
+        let mut releases: Vec<DateTime<Utc>> = Vec::new();
+        for time in sorted {
+            match releases.last() {
+                Some(last) if (time - *last).num_seconds() <= MERGE_WINDOW => {}
+                _ => releases.push(time),
+            }
+        }
+        if releases.len() < MIN_RELEASES {
+            return None;
+        }
+
+        let mut gaps: Vec<i64> = releases
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_seconds())
+            .collect();
+        gaps.sort_unstable();
+        let typical_gap = gaps[gaps.len() / 2];
+
+        // เรื่องที่ออกราวสัปดาห์ละครั้งมักออกวันเดิมทุกสัปดาห์
+        let is_weekly = (6 * 24 * 60 * 60..=8 * 24 * 60 * 60).contains(&typical_gap);
+        let weekday = if is_weekly {
+            Self::most_common(releases.iter().map(|time| time.weekday()))
+                .filter(|(_, count)| Self::is_regular(*count, releases.len()))
+                .map(|(weekday, _)| weekday)
+        } else {
+            None
+        };
+
+        let hour_samples: Vec<&DateTime<Utc>> = releases
+            .iter()
+            .filter(|time| weekday.is_none_or(|weekday| time.weekday() == weekday))
+            .collect();
+        let hour = Self::most_common(hour_samples.iter().map(|time| time.hour()))
+            .filter(|(_, count)| Self::is_regular(*count, hour_samples.len()))
+            .map(|(hour, _)| hour);
+
+        Some(ReleasePattern {
+            typical_gap,
+            weekday,
+            hour,
+            last_release: *releases.last()?,
+            releases: releases.len(),
+        })
+    }
+
+    fn most_common<T: PartialEq + Copy>(values: impl Iterator<Item = T>) -> Option<(T, usize)> {
+        let mut counts: Vec<(T, usize)> = Vec::new();
+        for value in values {
+            match counts.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value, 1)),
+            }
+        }
+        counts.into_iter().max_by_key(|(_, count)| *count)
+    }
+
+    fn is_regular(count: usize, total: usize) -> bool {
+        total > 0 && count as f64 / total as f64 >= MIN_REGULAR_SHARE
+    }
+
+    // เวลาที่คาดว่าจะออกตอนถัดจากตอนล่าสุดที่พบ
+    fn expected_after_last(&self) -> DateTime<Utc> {
+        let estimate = self.last_release + Duration::seconds(self.typical_gap);
+        let Some(hour) = self.hour else {
+            return estimate;
+        };
+        let Some(at_hour) = Utc
+            .with_ymd_and_hms(
+                estimate.year(),
+                estimate.month(),
+                estimate.day(),
+                hour,
+                0,
+                0,
+            )
+            .single()
+        else {
+            return estimate;
+        };
+
+        match self.weekday {
+            // วันที่ตรงกับวันประจำที่ใกล้กับเวลาที่ประมาณไว้ที่สุด
+            Some(weekday) => {
+                let days = (weekday.num_days_from_monday() as i64
+                    - at_hour.weekday().num_days_from_monday() as i64)
+                    .rem_euclid(7);
+                let days = if days > 3 { days - 7 } else { days };
+                at_hour + Duration::days(days)
+            }
+            // ชั่วโมงประจำของวันที่ใกล้กับเวลาที่ประมาณไว้ที่สุด
+            None => {
+                let offset = (at_hour - estimate).num_hours();
+                if offset > 12 {
+                    at_hour - Duration::days(1)
+                } else if offset < -12 {
+                    at_hour + Duration::days(1)
+                } else {
+                    at_hour
+                }
+            }
+        }
+    }
+
+    // ระยะห่างระหว่างรอบที่คาดว่าจะออก เรื่องที่ออกวันหรือชั่วโมงเดิมจะเลื่อนทีละวันเต็มเพื่อให้ตรงเวลาเดิม
+    fn cycle(&self) -> Duration {
+        match (self.weekday, self.hour) {
+            (Some(_), _) => Duration::weeks(1),
+            (None, Some(_)) => {
+                Duration::days(((self.typical_gap as f64 / 86400.0).round() as i64).max(1))
+            }
+            (None, None) => Duration::seconds(self.typical_gap),
+        }
+    }
+
+    // เวลาที่คาดว่าจะออกตอนถัดไป ถ้าเลยช่วงที่คาดไว้มาแล้ว (เช่น เว้นไปหนึ่งสัปดาห์) จะเลื่อนไปรอบถัดไป
+    pub fn expected_next(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let expected = self.expected_after_last();
+        let earliest = now - Duration::seconds(RELEASE_WINDOW);
+        if expected >= earliest {
+            return expected;
+        }
+        let cycle = self.cycle().num_seconds();
+        let missed = ((earliest - expected).num_seconds() + cycle - 1) / cycle;
+        expected + Duration::seconds(missed * cycle)
+    }
+
+    // ไม่มีตอนใหม่นานเกินปกติมาก ถือว่าพักการออกตอนหรือจบแล้ว
+    pub fn is_on_hiatus(&self, now: DateTime<Utc>) -> bool {
+        (now - self.last_release).num_seconds() > (self.typical_gap * HIATUS_GAPS).max(MIN_HIATUS)
+    }
+
+    // ระยะห่างการเช็คครั้งถัดไป (วินาที) เช็คถี่ช่วงใกล้เวลาที่คาดว่าจะออก
+    // ห่างออกไปเมื่อยังไม่ถึงเวลา และเช็คบ่อยขึ้นช่วงหลังรอบที่พลาดไปเผื่อตอนใหม่ออกช้า
+    pub fn check_interval_at(&self, now: DateTime<Utc>) -> i64 {
+        if self.is_on_hiatus(now) {
+            return DORMANT_CHECK_INTERVAL;
+        }
+
+        let expected = self.expected_next(now);
+        let until_release = (expected - now).num_seconds();
+        if until_release <= RELEASE_WINDOW {
+            return PEAK_CHECK_INTERVAL;
+        }
+
+        // ตื่นมาเช็คอีกครั้งตอนเริ่มช่วงที่คาดว่าจะออก
+        let until_window =
+            (until_release - RELEASE_WINDOW).clamp(PEAK_CHECK_INTERVAL, MAX_IDLE_CHECK_INTERVAL);
+        let previous = expected - self.cycle();
+        let since_missed = (now - previous).num_seconds();
+        let late_window = LATE_RELEASE_WINDOW.min(self.cycle().num_seconds() / 2);
+        if previous > self.last_release && since_missed <= late_window {
+            until_window.min(HOT_CHECK_INTERVAL)
+        } else {
+            until_window
+        }
+    }
+
+    // คำอธิบายรูปแบบการออกตอน แสดงวันและเวลาตามเขตเวลาที่กำหนด
+    pub fn describe<Tz: TimeZone>(&self, tz: &Tz) -> String {
+        let expected = self.expected_after_last().with_timezone(tz);
+        match (self.weekday, self.hour) {
+            (Some(_), Some(_)) => format!(
+                "ทุก{} ราว {:02}:00",
+                WEEKDAY_NAMES[expected.weekday().num_days_from_monday() as usize],
+                expected.hour()
+            ),
+            (Some(_), None) => format!(
+                "ทุก{}",
+                WEEKDAY_NAMES[expected.weekday().num_days_from_monday() as usize]
+            ),
+            (None, Some(_)) => format!(
+                "ประมาณทุก {} ราว {:02}:00",
+                Self::describe_gap(self.typical_gap),
+                expected.hour()
+            ),
+            (None, None) => format!("ประมาณทุก {}", Self::describe_gap(self.typical_gap)),
+        }
+    }
+
+    fn describe_gap(seconds: i64) -> String {
+        let hours = (seconds as f64 / 3600.0).round() as i64;
+        if hours < 48 {
+            format!("{} ชั่วโมง", hours.max(1))
+        } else {
+            format!("{} วัน", (hours as f64 / 24.0).round() as i64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    // 2024-01-01 เป็นวันจันทร์ ออกทุกวันจันทร์ 12:00 และพบตอนซ้ำอีกครั้งในรอบสุดท้าย
+    fn weekly_monday_noon() -> ReleasePattern {
+        ReleasePattern::learn(&[
+            at(22, 12, 30),
+            at(1, 12, 0),
+            at(8, 12, 0),
+            at(15, 12, 0),
+            at(22, 12, 0),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn learn_needs_enough_separate_releases() {
+        assert_eq!(ReleasePattern::learn(&[]), None);
+        // สองตอนที่พบห่างกันไม่เกิน MERGE_WINDOW นับเป็นการออกครั้งเดียว
+        assert_eq!(
+            ReleasePattern::learn(&[at(1, 12, 0), at(1, 15, 0), at(8, 12, 0), at(15, 12, 0)]),
+            None
+        );
+    }
+
+    #[test]
+    fn learn_detects_weekly_weekday_and_hour() {
+        let pattern = weekly_monday_noon();
+        assert_eq!(pattern.typical_gap, 7 * 24 * 60 * 60);
+        assert_eq!(pattern.weekday, Some(Weekday::Mon));
+        assert_eq!(pattern.hour, Some(12));
+        assert_eq!(pattern.releases, 4);
+        assert_eq!(pattern.last_release, at(22, 12, 0));
+    }
+
+    #[test]
+    fn expected_next_follows_the_weekly_slot() {
+        let pattern = weekly_monday_noon();
+        assert_eq!(pattern.expected_next(at(25, 0, 0)), at(29, 12, 0));
+        // ยังอยู่ในช่วงที่คาดว่าจะออก แม้จะเลยเวลาไปแล้ว
+        assert_eq!(pattern.expected_next(at(29, 13, 0)), at(29, 12, 0));
+        // เว้นไปหนึ่งสัปดาห์ เลื่อนไปรอบถัดไป
+        assert_eq!(
+            pattern.expected_next(at(31, 0, 0)),
+            Utc.with_ymd_and_hms(2024, 2, 5, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn expected_next_snaps_to_the_regular_hour() {
+        let pattern =
+            ReleasePattern::learn(&[at(1, 8, 0), at(2, 8, 0), at(3, 8, 0), at(4, 10, 0)]).unwrap();
+        assert_eq!(pattern.weekday, None);
+        assert_eq!(pattern.hour, Some(8));
+        assert_eq!(pattern.expected_next(at(4, 12, 0)), at(5, 8, 0));
+    }
+
+    #[test]
+    fn irregular_releases_use_the_median_gap() {
+        let pattern =
+            ReleasePattern::learn(&[at(1, 1, 0), at(2, 5, 0), at(5, 9, 0), at(7, 13, 0)]).unwrap();
+        assert_eq!(pattern.weekday, None);
+        assert_eq!(pattern.hour, None);
+        assert_eq!(pattern.typical_gap, (2 * 24 + 4) * 60 * 60);
+        assert_eq!(pattern.expected_next(at(7, 14, 0)), at(9, 17, 0));
+    }
+}
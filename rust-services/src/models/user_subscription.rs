@@ -0,0 +1,33 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+// การติดตามมังงะส่วนตัวของผู้ใช้ ตอนใหม่จะถูกส่งทาง DM โดยไม่ขึ้นกับช่องของเซิร์ฟเวอร์
+// unique ตาม user_id + manga_id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserSubscription {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: String,
+    pub manga_id: ObjectId,
+    pub manga_title: String,
+    // เวลาที่ส่ง DM ไม่สำเร็จเพราะผู้ใช้ปิดรับ DM ระหว่างนี้จะไม่ส่งจนกว่าผู้ใช้จะเปิดรับอีกครั้ง
+    #[serde(default)]
+    pub dm_closed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl UserSubscription {
+    pub fn new(user_id: String, manga_id: ObjectId, manga_title: String) -> Self {
+        let now = DateTime::from(std::time::SystemTime::now());
+        UserSubscription {
+            id: None,
+            user_id,
+            manga_id,
+            manga_title,
+            dm_closed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
@@ -1,2 +1,12 @@
 pub mod channels_service;
+pub mod chapter_service;
+pub mod guild_settings_service;
+pub mod guild_subscription_service;
+pub mod manga_role_service;
 pub mod manga_service;
+pub mod manga_thread_service;
+pub mod page_cache_service;
+pub mod pending_notification_service;
+pub mod reading_progress_service;
+pub mod user_service;
+pub mod user_subscription_service;
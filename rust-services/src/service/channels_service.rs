@@ -1,9 +1,19 @@
-use crate::models::channels::Channel;
+use crate::models::channels::{Channel, ChannelLayout, ChannelWebhook, DeliveryMode};
 use crate::utils::mongo;
 use mongodb::bson::{doc, oid::ObjectId};
 use futures::TryStreamExt;
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
 
 
+// ค่าที่ต้องการเปลี่ยนของช่องที่ลงทะเบียนไว้แล้ว None = คงค่าเดิม, Some(None) = ล้างค่า
+#[derive(Default)]
+pub struct ChannelUpdate {
+    pub purpose: Option<Option<String>>,
+    pub title_filter: Option<Option<String>>,
+    pub layout: Option<ChannelLayout>,
+}
+
 pub struct ChannelsService;
 
 impl ChannelsService {
@@ -13,34 +23,146 @@ impl ChannelsService {
         db_pool.collection::<Channel>("channels")
     }
 
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "channel_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn create_channel(channel: Channel) -> Result<ObjectId, mongodb::error::Error> {
         let collection = Self::get_collection().await;
         let result = collection.insert_one(channel).await?;
         Ok(result.inserted_id.as_object_id().unwrap())
     }
 
-    pub async fn update_channel( channel: &Channel) -> Result<(), mongodb::error::Error> {
+    // อัพเดทชื่อช่องและชื่อเซิร์ฟเวอร์ และเปลี่ยนเฉพาะค่าที่ระบุใน changes
+    pub async fn update_channel(
+        channel: &Channel,
+        changes: &ChannelUpdate,
+    ) -> Result<(), mongodb::error::Error> {
         let collection = Self::get_collection().await;
         let now = mongodb::bson::DateTime::from(std::time::SystemTime::now());
 
+        let mut set = doc! {
+            "channel_name": &channel.channel_name,
+            "guild_name": &channel.guild_name,
+            "updated_at": now
+        };
+        if let Some(purpose) = &changes.purpose {
+            set.insert("purpose", purpose);
+        }
+        if let Some(title_filter) = &changes.title_filter {
+            set.insert("title_filter", title_filter);
+        }
+        if let Some(layout) = &changes.layout {
+            set.insert("layout", mongodb::bson::to_bson(layout)?);
+        }
+
         collection
             .update_one(
                 doc! {
-                    "guild_id": &channel.guild_id
+                    "guild_id": &channel.guild_id,
+                    "channel_id": &channel.channel_id
                 },
+                doc! { "$set": set },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // เปลี่ยนวิธีส่งการแจ้งเตือนของช่อง คืน false ถ้าไม่พบช่อง
+    pub async fn set_delivery_mode(
+        guild_id: &str,
+        channel_id: &str,
+        delivery_mode: &DeliveryMode,
+    ) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = mongodb::bson::DateTime::from(std::time::SystemTime::now());
+
+        let result = collection
+            .update_one(
+                doc! { "guild_id": guild_id, "channel_id": channel_id },
                 doc! {
                     "$set": {
-                        "channel_name": &channel.channel_name,
-                        "channel_id": &channel.channel_id,
-                        "guild_name": &channel.guild_name,
+                        "delivery_mode": mongodb::bson::to_bson(delivery_mode)?,
+                        "last_digest_at": now,
                         "updated_at": now
                     }
                 },
             )
             .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    // บันทึกเวลาที่ส่งสรุปของช่องล่าสุด
+    pub async fn mark_digest_sent(
+        guild_id: &str,
+        channel_id: &str,
+        sent_at: mongodb::bson::DateTime,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .update_one(
+                doc! { "guild_id": guild_id, "channel_id": channel_id },
+                doc! { "$set": { "last_digest_at": sent_at } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // บันทึก webhook ของช่อง ส่ง None เพื่อเลิกใช้ webhook
+    pub async fn set_webhook(
+        guild_id: &str,
+        channel_id: &str,
+        webhook: Option<&ChannelWebhook>,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = mongodb::bson::DateTime::from(std::time::SystemTime::now());
+        let webhook = webhook
+            .map(mongodb::bson::to_bson)
+            .transpose()?
+            .unwrap_or(mongodb::bson::Bson::Null);
+
+        collection
+            .update_one(
+                doc! { "guild_id": guild_id, "channel_id": channel_id },
+                doc! { "$set": { "webhook": webhook, "updated_at": now } },
+            )
+            .await?;
         Ok(())
     }
 
+    pub async fn get_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<Option<Channel>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .find_one(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .await
+    }
+
+    // ลบช่องออกจากระบบ คืน true ถ้ามีเอกสารถูกลบ
+    pub async fn delete_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_one(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+
     pub async fn get_all_channels() -> Result<Vec<Channel>, mongodb::error::Error> {
         let collection = Self::get_collection().await;
         let mut cursor = collection.find(doc! {}).await?;
@@ -0,0 +1,229 @@
+use crate::models::chapter::Chapter;
+use crate::models::chapter_number::ChapterNumber;
+use crate::sources::ScrapedChapter;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use std::collections::HashSet;
+
+pub struct ChapterService;
+
+#[allow(dead_code)]
+impl ChapterService {
+    pub async fn get_collection() -> mongodb::Collection<Chapter> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<Chapter>("chapters")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "manga_id": 1, "url": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "first_seen_at": -1 })
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "manga_id": 1, "number_key": -1 })
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    // URL ของตอนทั้งหมดที่เคยบันทึกไว้ของมังงะเรื่องนี้
+    pub async fn get_known_urls(
+        manga_id: &ObjectId,
+    ) -> Result<HashSet<String>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let urls = collection
+            .distinct("url", doc! { "manga_id": manga_id })
+            .await?;
+        Ok(urls
+            .into_iter()
+            .filter_map(|url| url.as_str().map(|url| url.to_string()))
+            .collect())
+    }
+
+    // บันทึกตอนที่ยังไม่เคยพบ แล้วคืนเฉพาะตอนที่เพิ่มใหม่
+    pub async fn record_chapters(
+        manga_id: &ObjectId,
+        manga_title: &str,
+        chapters: &[ScrapedChapter],
+        scrape_run_id: &ObjectId,
+    ) -> Result<Vec<Chapter>, mongodb::error::Error> {
+        let known_urls = Self::get_known_urls(manga_id).await?;
+
+        let new_chapters: Vec<Chapter> = chapters
+            .iter()
+            .filter(|chapter| !known_urls.contains(&chapter.url))
+            .map(|chapter| {
+                Chapter::new(
+                    *manga_id,
+                    manga_title.to_string(),
+                    chapter.number.clone(),
+                    chapter.title.clone(),
+                    chapter.url.clone(),
+                    chapter.release_date.clone(),
+                    *scrape_run_id,
+                )
+            })
+            .collect();
+
+        if !new_chapters.is_empty() {
+            let collection = Self::get_collection().await;
+            collection.insert_many(&new_chapters).await?;
+        }
+        Ok(new_chapters)
+    }
+
+    // ย้ายประวัติตอนไปเป็นของมังงะอีกเรื่อง ตอนที่อีกเรื่องมีอยู่แล้ว (URL เดียวกัน) จะถูกลบทิ้ง
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let known_urls: Vec<String> = Self::get_known_urls(to_manga_id)
+            .await?
+            .into_iter()
+            .collect();
+
+        collection
+            .delete_many(doc! { "manga_id": from_manga_id, "url": { "$in": &known_urls } })
+            .await?;
+        collection
+            .update_many(
+                doc! { "manga_id": from_manga_id },
+                doc! { "$set": { "manga_id": to_manga_id } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // ลบประวัติตอนทั้งหมดของมังงะหนึ่งเรื่อง
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    pub async fn count_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .count_documents(doc! { "manga_id": manga_id })
+            .await
+    }
+
+    // ตอนทั้งหมดที่บันทึกไว้ของมังงะหนึ่งเรื่อง ไม่เรียงลำดับ
+    pub async fn get_by_manga(manga_id: &ObjectId) -> Result<Vec<Chapter>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": manga_id }).await?;
+        let mut chapters = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            chapters.push(doc);
+        }
+        Ok(chapters)
+    }
+
+    // ประวัติตอนของมังงะหนึ่งเรื่อง เรียงจากตอนใหม่ไปเก่า
+    pub async fn get_history(
+        manga_id: &ObjectId,
+        limit: i64,
+    ) -> Result<Vec<Chapter>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "manga_id": manga_id })
+            .sort(doc! { "number_key": -1 })
+            .limit(limit)
+            .await?;
+        let mut chapters = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            chapters.push(doc);
+        }
+        Ok(chapters)
+    }
+
+    // จำนวนตอนที่ใหม่กว่าตอนที่ระบุ ใช้นับตอนที่ผู้ใช้ยังไม่ได้อ่าน
+    pub async fn count_newer_than(
+        manga_id: &ObjectId,
+        number: &ChapterNumber,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .count_documents(doc! {
+                "manga_id": manga_id,
+                "number_key": { "$gt": number.sort_key() }
+            })
+            .await
+    }
+
+    // เวลาที่พบตอนใหม่ล่าสุดของมังงะหนึ่งเรื่อง
+    pub async fn last_seen_at(
+        manga_id: &ObjectId,
+    ) -> Result<Option<mongodb::bson::DateTime>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let latest = collection
+            .find_one(doc! { "manga_id": manga_id })
+            .sort(doc! { "first_seen_at": -1 })
+            .await?;
+        Ok(latest.map(|chapter| chapter.first_seen_at))
+    }
+
+    // เวลาที่พบตอนใหม่ของแต่ละรอบการดึงข้อมูล เรียงจากเก่าไปใหม่
+    // ไม่นับรอบแรกเพราะเป็นการบันทึกตอนเก่าทั้งหมดตอนเพิ่มเรื่อง ไม่ใช่เวลาที่ออกตอนจริง
+    pub async fn get_release_times(
+        manga_id: &ObjectId,
+    ) -> Result<Vec<chrono::DateTime<chrono::Utc>>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .aggregate(vec![
+                doc! { "$match": { "manga_id": manga_id } },
+                doc! {
+                    "$group": { "_id": "$scrape_run_id", "seen_at": { "$min": "$first_seen_at" } }
+                },
+                doc! { "$sort": { "seen_at": 1 } },
+            ])
+            .await?;
+        let mut times = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            if let Ok(seen_at) = doc.get_datetime("seen_at") {
+                times.push(chrono::DateTime::<chrono::Utc>::from(
+                    seen_at.to_system_time(),
+                ));
+            }
+        }
+        Ok(times.into_iter().skip(1).collect())
+    }
+
+    // ตอนที่พบล่าสุดของทุกเรื่อง เรียงตามเวลาที่ตรวจพบ
+    pub async fn get_recent_releases(limit: i64) -> Result<Vec<Chapter>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! {})
+            .sort(doc! { "first_seen_at": -1 })
+            .limit(limit)
+            .await?;
+        let mut chapters = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            chapters.push(doc);
+        }
+        Ok(chapters)
+    }
+}
@@ -0,0 +1,102 @@
+use crate::models::guild_settings::{GuildSettings, QuietHours};
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use std::collections::HashMap;
+
+pub struct GuildSettingsService;
+
+#[allow(dead_code)]
+impl GuildSettingsService {
+    pub async fn get_collection() -> mongodb::Collection<GuildSettings> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<GuildSettings>("guild_settings")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    // การตั้งค่าของเซิร์ฟเวอร์ ถ้ายังไม่เคยตั้งค่าจะคืนค่าเริ่มต้น
+    pub async fn get(guild_id: &str) -> Result<GuildSettings, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let settings = collection.find_one(doc! { "guild_id": guild_id }).await?;
+        Ok(settings.unwrap_or_else(|| GuildSettings::new(guild_id.to_string())))
+    }
+
+    // การตั้งค่าของทุกเซิร์ฟเวอร์ที่เคยตั้งค่าไว้ แยกตาม guild_id
+    pub async fn get_all() -> Result<HashMap<String, GuildSettings>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! {}).await?;
+        let mut settings = HashMap::new();
+        while let Some(doc) = cursor.try_next().await? {
+            settings.insert(doc.guild_id.clone(), doc);
+        }
+        Ok(settings)
+    }
+
+    // บันทึกค่าที่เปลี่ยน ถ้ายังไม่มีเอกสารจะสร้างใหม่โดยใช้ค่าเริ่มต้นกับค่าที่เหลือ
+    async fn set(
+        guild_id: &str,
+        changes: mongodb::bson::Document,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = DateTime::from(std::time::SystemTime::now());
+        let defaults = GuildSettings::new(guild_id.to_string());
+
+        let mut set_on_insert = doc! { "created_at": now };
+        for (key, value) in [
+            ("timezone", defaults.timezone.into()),
+            ("locale", defaults.locale.into()),
+            ("quiet_hours", mongodb::bson::Bson::Null),
+        ] {
+            if !changes.contains_key(key) {
+                set_on_insert.insert(key, value);
+            }
+        }
+
+        let mut set = changes;
+        set.insert("updated_at", now);
+        collection
+            .update_one(
+                doc! { "guild_id": guild_id },
+                doc! { "$set": set, "$setOnInsert": set_on_insert },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_timezone(guild_id: &str, timezone: &str) -> Result<(), mongodb::error::Error> {
+        Self::set(guild_id, doc! { "timezone": timezone }).await
+    }
+
+    pub async fn set_locale(guild_id: &str, locale: &str) -> Result<(), mongodb::error::Error> {
+        Self::set(guild_id, doc! { "locale": locale }).await
+    }
+
+    // ตั้งช่วงเวลางดแจ้งเตือน ส่ง None เพื่อยกเลิก
+    pub async fn set_quiet_hours(
+        guild_id: &str,
+        quiet_hours: Option<&QuietHours>,
+    ) -> Result<(), mongodb::error::Error> {
+        Self::set(
+            guild_id,
+            doc! { "quiet_hours": mongodb::bson::to_bson(&quiet_hours)? },
+        )
+        .await
+    }
+}
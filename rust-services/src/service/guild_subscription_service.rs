@@ -0,0 +1,174 @@
+use crate::models::guild_subscription::GuildSubscription;
+use crate::service::channels_service::ChannelsService;
+use crate::service::manga_service::MangaService;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+// ชื่อ migration ที่ให้เซิร์ฟเวอร์เดิมติดตามมังงะทุกเรื่อง
+const SEED_EXISTING_GUILDS_MIGRATION: &str = "seed_guild_subscriptions";
+
+pub struct GuildSubscriptionService;
+
+#[allow(dead_code)]
+impl GuildSubscriptionService {
+    pub async fn get_collection() -> mongodb::Collection<GuildSubscription> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<GuildSubscription>("guild_subscriptions")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "manga_id": 1, "channel_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "manga_id": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    // ติดตามมังงะ คืน false ถ้าเคยติดตามไว้แล้ว
+    pub async fn subscribe(
+        subscription: &GuildSubscription,
+    ) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        match collection.insert_one(subscription).await {
+            Ok(_) => Ok(true),
+            Err(e) if mongo::is_duplicate_key_error(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // เลิกติดตามมังงะ ถ้าไม่ระบุช่องจะเลิกติดตามทุกช่องของเซิร์ฟเวอร์ คืนจำนวนที่ถูกลบ
+    pub async fn unsubscribe(
+        guild_id: &str,
+        manga_id: &ObjectId,
+        channel_id: Option<&str>,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut filter = doc! { "guild_id": guild_id, "manga_id": manga_id };
+        if let Some(channel_id) = channel_id {
+            filter.insert("channel_id", channel_id);
+        }
+        let result = collection.delete_many(filter).await?;
+        Ok(result.deleted_count)
+    }
+
+    pub async fn get_by_manga(
+        manga_id: &ObjectId,
+    ) -> Result<Vec<GuildSubscription>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": manga_id }).await?;
+        let mut subscriptions = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            subscriptions.push(doc);
+        }
+        Ok(subscriptions)
+    }
+
+    pub async fn get_by_guild(
+        guild_id: &str,
+    ) -> Result<Vec<GuildSubscription>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "guild_id": guild_id })
+            .sort(doc! { "manga_title": 1 })
+            .await?;
+        let mut subscriptions = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            subscriptions.push(doc);
+        }
+        Ok(subscriptions)
+    }
+
+    // จำนวนการติดตามของมังงะเรื่องนี้จากทุกเซิร์ฟเวอร์
+    pub async fn count_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .count_documents(doc! { "manga_id": manga_id })
+            .await
+    }
+
+    // ลบการติดตามทั้งหมดของมังงะที่ถูกลบออกจากระบบ
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบการติดตามที่ส่งไปยังช่องที่ถูกลบออกจากระบบ
+    pub async fn delete_by_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // migration สำหรับเซิร์ฟเวอร์ที่ลงทะเบียนช่องไว้ก่อนมีการติดตามรายเรื่อง
+    // เดิมทุกช่องได้รับการอัพเดทของทุกเรื่อง จึงให้เซิร์ฟเวอร์เหล่านั้นติดตามมังงะทุกเรื่องที่มีอยู่
+    // ทำครั้งเดียว การเลิกติดตามหลังจากนี้จะไม่ถูกเพิ่มกลับ คืนจำนวนการติดตามที่เพิ่ม
+    pub async fn seed_existing_guilds() -> Result<u64, mongodb::error::Error> {
+        if mongo::is_migration_applied(SEED_EXISTING_GUILDS_MIGRATION).await? {
+            return Ok(0);
+        }
+
+        let mut guild_ids: Vec<String> = ChannelsService::get_all_channels()
+            .await?
+            .into_iter()
+            .map(|channel| channel.guild_id)
+            .collect();
+        guild_ids.sort();
+        guild_ids.dedup();
+
+        let mangas = MangaService::get_all().await?;
+        let mut seeded = 0;
+        for guild_id in &guild_ids {
+            for manga in &mangas {
+                let Some(manga_id) = manga.id else {
+                    continue;
+                };
+                let subscription =
+                    GuildSubscription::new(guild_id.clone(), manga_id, manga.title.clone(), None);
+                if Self::subscribe(&subscription).await? {
+                    seeded += 1;
+                }
+            }
+        }
+
+        mongo::mark_migration_applied(SEED_EXISTING_GUILDS_MIGRATION).await?;
+        Ok(seeded)
+    }
+
+    // ย้ายการติดตามไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        for subscription in Self::get_by_manga(from_manga_id).await? {
+            Self::subscribe(&GuildSubscription {
+                id: None,
+                manga_id: *to_manga_id,
+                ..subscription
+            })
+            .await?;
+        }
+        Self::delete_by_manga(from_manga_id).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::models::manga_role::MangaRole;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+pub struct MangaRoleService;
+
+#[allow(dead_code)]
+impl MangaRoleService {
+    pub async fn get_collection() -> mongodb::Collection<MangaRole> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<MangaRole>("manga_roles")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "manga_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "manga_id": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    // ผูก role กับมังงะ ถ้าเคยผูกไว้แล้วจะเปลี่ยนเป็น role ใหม่
+    pub async fn link(role: &MangaRole) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = DateTime::from(std::time::SystemTime::now());
+
+        collection
+            .update_one(
+                doc! { "guild_id": &role.guild_id, "manga_id": role.manga_id },
+                doc! {
+                    "$set": {
+                        "manga_title": &role.manga_title,
+                        "role_id": &role.role_id,
+                        "updated_at": now
+                    },
+                    "$setOnInsert": { "created_at": now }
+                },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    // ยกเลิกการผูก role คืน true ถ้ามีข้อมูลถูกลบ
+    pub async fn unlink(
+        guild_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_one(doc! { "guild_id": guild_id, "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn get(
+        guild_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<Option<MangaRole>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .find_one(doc! { "guild_id": guild_id, "manga_id": manga_id })
+            .await
+    }
+
+    pub async fn get_by_manga(
+        manga_id: &ObjectId,
+    ) -> Result<Vec<MangaRole>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": manga_id }).await?;
+        let mut roles = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            roles.push(doc);
+        }
+        Ok(roles)
+    }
+
+    pub async fn get_by_guild(guild_id: &str) -> Result<Vec<MangaRole>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "guild_id": guild_id }).await?;
+        let mut roles = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            roles.push(doc);
+        }
+        Ok(roles)
+    }
+
+    // ลบการผูก role ทั้งหมดของมังงะที่ถูกลบออกจากระบบ
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ย้ายการผูก role ไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    // ถ้าเซิร์ฟเวอร์ผูก role กับทั้งสองเรื่องไว้ จะใช้ role ของเรื่องปลายทาง
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        for role in Self::get_by_manga(from_manga_id).await? {
+            if Self::get(&role.guild_id, to_manga_id).await?.is_none() {
+                Self::link(&MangaRole {
+                    manga_id: *to_manga_id,
+                    ..role
+                })
+                .await?;
+            }
+        }
+        Self::delete_by_manga(from_manga_id).await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,26 @@
 use crate::models::manga::Manga;
-use crate::utils::mongo;
+use crate::service::chapter_service::ChapterService;
+use crate::service::guild_subscription_service::GuildSubscriptionService;
+use crate::service::manga_role_service::MangaRoleService;
+use crate::service::manga_thread_service::MangaThreadService;
+use crate::service::pending_notification_service::PendingNotificationService;
+use crate::service::reading_progress_service::ReadingProgressService;
+use crate::service::user_subscription_service::UserSubscriptionService;
+use crate::utils::url::canonicalize;
+use crate::utils::{fuzzy, mongo};
 use futures::TryStreamExt;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use std::collections::HashMap;
 
 pub struct MangaService;
 
+// filter สำหรับค้นหามังงะจาก URL ใด ๆ ที่ชี้ไปหน้าเดียวกัน
+fn url_filter(url: &str) -> Document {
+    doc! { "canonical_url": canonicalize(url).unwrap_or_else(|| url.to_string()) }
+}
+
 #[allow(dead_code)]
 impl MangaService {
     pub async fn get_collection() -> mongodb::Collection<Manga> {
@@ -12,15 +28,336 @@ impl MangaService {
         db_pool.collection::<Manga>("mangas")
     }
 
-    pub async fn create(manga: &Manga) -> Result<(), mongodb::error::Error> {
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
         let collection = Self::get_collection().await;
-        collection.insert_one(manga).await?;
+
+        // ใช้กับการค้นหาชื่อเรื่องแบบขึ้นต้นด้วยคำค้น
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "title": 1 }).build())
+            .await?;
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "alt_titles": 1 }).build())
+            .await?;
+        // ใช้ดึงเรื่องที่ถึงเวลาเช็คอัพเดท
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "next_check_at": 1 })
+                    .build(),
+            )
+            .await?;
+        // ต้องเรียก merge_duplicate_urls ก่อน ไม่เช่นนั้นข้อมูลซ้ำเดิมจะทำให้สร้าง index ไม่ได้
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "canonical_url": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
         Ok(())
     }
 
+    // migration สำหรับข้อมูลที่เพิ่มก่อนมี canonical_url
+    // บันทึก canonical_url ให้ทุกเรื่อง และรวมเรื่องที่ URL ชี้ไปหน้าเดียวกันเข้ากับเรื่องที่เพิ่มก่อน
+    // คืนจำนวนเรื่องซ้ำที่ถูกรวม เรียกซ้ำได้โดยไม่มีผลเมื่อไม่มีข้อมูลซ้ำ
+    pub async fn merge_duplicate_urls() -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        let mut groups: HashMap<String, Vec<Manga>> = HashMap::new();
+        for manga in Self::get_all().await? {
+            let canonical_url = canonicalize(&manga.url).unwrap_or_else(|| manga.url.clone());
+            groups.entry(canonical_url).or_default().push(manga);
+        }
+
+        let mut merged = 0;
+        for (canonical_url, mut mangas) in groups {
+            mangas.sort_by_key(|manga| manga.created_at);
+            let mut mangas = mangas.into_iter();
+            let Some(mut primary) = mangas.next() else {
+                continue;
+            };
+            let Some(primary_id) = primary.id else {
+                continue;
+            };
+            let duplicates: Vec<Manga> = mangas.collect();
+
+            if duplicates.is_empty() && primary.canonical_url == canonical_url {
+                continue;
+            }
+
+            for duplicate in duplicates {
+                let Some(duplicate_id) = duplicate.id else {
+                    continue;
+                };
+
+                // ใช้ข้อมูลตอนล่าสุดที่ใหม่ที่สุดในกลุ่ม
+                if duplicate.latest_chapter > primary.latest_chapter {
+                    primary.latest_chapter = duplicate.latest_chapter;
+                    primary.latest_chapter_url = duplicate.latest_chapter_url;
+                }
+                for title in std::iter::once(duplicate.title).chain(duplicate.alt_titles) {
+                    if title != primary.title && !primary.alt_titles.contains(&title) {
+                        primary.alt_titles.push(title);
+                    }
+                }
+                primary.last_checked_at = primary.last_checked_at.max(duplicate.last_checked_at);
+
+                ChapterService::move_to_manga(&duplicate_id, &primary_id).await?;
+                GuildSubscriptionService::move_to_manga(&duplicate_id, &primary_id).await?;
+                UserSubscriptionService::move_to_manga(&duplicate_id, &primary_id).await?;
+                ReadingProgressService::move_to_manga(&duplicate_id, &primary_id).await?;
+                MangaRoleService::move_to_manga(&duplicate_id, &primary_id).await?;
+                PendingNotificationService::move_to_manga(&duplicate_id, &primary_id).await?;
+                MangaThreadService::move_to_manga(&duplicate_id, &primary_id).await?;
+                collection.delete_one(doc! { "_id": duplicate_id }).await?;
+                merged += 1;
+            }
+
+            collection
+                .update_one(
+                    doc! { "_id": primary_id },
+                    doc! {
+                        "$set": {
+                            "canonical_url": &canonical_url,
+                            "alt_titles": &primary.alt_titles,
+                            "latest_chapter": &primary.latest_chapter,
+                            "latest_chapter_url": &primary.latest_chapter_url,
+                            "last_checked_at": primary.last_checked_at,
+                        }
+                    },
+                )
+                .await?;
+        }
+        Ok(merged)
+    }
+
+    pub async fn create(manga: &Manga) -> Result<ObjectId, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection.insert_one(manga).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
     pub async fn get_by_url(url: &str) -> Result<Option<Manga>, mongodb::error::Error> {
         let collection: mongodb::Collection<Manga> = Self::get_collection().await;
-        collection.find_one(doc! { "url": url }).await
+        collection.find_one(url_filter(url)).await
+    }
+
+    pub async fn get_by_id(id: &ObjectId) -> Result<Option<Manga>, mongodb::error::Error> {
+        let collection: mongodb::Collection<Manga> = Self::get_collection().await;
+        collection.find_one(doc! { "_id": id }).await
+    }
+
+    // ลบมังงะ คืน true ถ้ามีเอกสารถูกลบ
+    pub async fn delete_by_id(id: &ObjectId) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection.delete_one(doc! { "_id": id }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn delete_by_url(url: &str) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection.delete_one(url_filter(url)).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn count() -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection.count_documents(doc! {}).await
+    }
+
+    // ดึงมังงะทีละหน้า เรียงตามชื่อเรื่อง (page เริ่มที่ 0)
+    pub async fn get_page(page: u64, page_size: u64) -> Result<Vec<Manga>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! {})
+            .sort(doc! { "title": 1 })
+            .skip(page * page_size)
+            .limit(page_size as i64)
+            .await?;
+        let mut mangas = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            mangas.push(doc);
+        }
+        Ok(mangas)
+    }
+
+    // มังงะที่ชื่อเรื่องหรือชื่ออื่นตรงกับข้อความทั้งหมด (ไม่สนตัวพิมพ์เล็กใหญ่)
+    pub async fn get_by_exact_title(title: &str) -> Result<Option<Manga>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let pattern = format!("^{}$", regex::escape(title.trim()));
+        collection
+            .find_one(doc! {
+                "$or": [
+                    { "title": { "$regex": &pattern, "$options": "i" } },
+                    { "alt_titles": { "$regex": &pattern, "$options": "i" } },
+                ]
+            })
+            .await
+    }
+
+    // ค้นหามังงะที่ชื่อเรื่องหรือชื่ออื่นขึ้นต้นด้วยคำค้น (ไม่สนตัวพิมพ์เล็กใหญ่) เรียงตามความใกล้เคียง
+    pub async fn search(query: &str, limit: usize) -> Result<Vec<Manga>, mongodb::error::Error> {
+        let query = query.trim();
+        let collection = Self::get_collection().await;
+
+        let filter = if query.is_empty() {
+            doc! {}
+        } else {
+            let pattern = format!("^{}", regex::escape(query));
+            doc! {
+                "$or": [
+                    { "title": { "$regex": &pattern, "$options": "i" } },
+                    { "alt_titles": { "$regex": &pattern, "$options": "i" } },
+                ]
+            }
+        };
+
+        let mut cursor = collection
+            .find(filter)
+            .sort(doc! { "title": 1 })
+            .limit(100)
+            .await?;
+        let mut mangas = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            mangas.push(doc);
+        }
+        let mut ranked: Vec<(u32, Manga)> = mangas
+            .into_iter()
+            .filter_map(|manga| {
+                std::iter::once(&manga.title)
+                    .chain(manga.alt_titles.iter())
+                    .filter_map(|title| fuzzy::match_score(query, title))
+                    .min()
+                    .map(|score| (score, manga))
+            })
+            .collect();
+        ranked.sort_by(|(a_score, a), (b_score, b)| {
+            a_score.cmp(b_score).then_with(|| a.title.cmp(&b.title))
+        });
+
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, manga)| manga)
+            .collect())
+    }
+
+    // บันทึกว่าเช็คอัพเดทสำเร็จแล้ว และตั้งเวลาเช็คครั้งถัดไปตามระยะห่างที่กำหนด (วินาที)
+    pub async fn mark_checked(url: &str, check_interval: i64) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = mongodb::bson::DateTime::from(std::time::SystemTime::now());
+        let next_check_at =
+            mongodb::bson::DateTime::from_millis(now.timestamp_millis() + check_interval * 1000);
+
+        collection
+            .update_one(
+                url_filter(url),
+                doc! {
+                    "$set": {
+                        "last_checked_at": now,
+                        "next_check_at": next_check_at,
+                        "check_interval": check_interval,
+                        "consecutive_failures": 0,
+                        "failing_since": null,
+                        "last_error": null,
+                        "failure_alerted": false
+                    }
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // บันทึกว่าเช็คไม่สำเร็จ และเลื่อนการเช็คครั้งถัดไป
+    pub async fn record_failure(
+        url: &str,
+        failing_since: mongodb::bson::DateTime,
+        error: &str,
+        next_check_at: mongodb::bson::DateTime,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .update_one(
+                url_filter(url),
+                doc! {
+                    "$inc": { "consecutive_failures": 1 },
+                    "$set": {
+                        "failing_since": failing_since,
+                        "last_error": error,
+                        "next_check_at": next_check_at
+                    }
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failure_alerted(url: &str) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .update_one(
+                url_filter(url),
+                doc! { "$set": { "failure_alerted": true } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn reschedule(
+        url: &str,
+        next_check_at: mongodb::bson::DateTime,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .update_one(
+                url_filter(url),
+                doc! { "$set": { "next_check_at": next_check_at } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // มังงะที่ถึงเวลาเช็คอัพเดทแล้ว เรียงจากเรื่องที่เลยเวลามานานที่สุด
+    pub async fn get_due(
+        now: mongodb::bson::DateTime,
+        limit: i64,
+    ) -> Result<Vec<Manga>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "next_check_at": { "$lte": now } })
+            .sort(doc! { "next_check_at": 1 })
+            .limit(limit)
+            .await?;
+        let mut mangas = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            mangas.push(doc);
+        }
+        Ok(mangas)
+    }
+
+    // ตั้งเวลาเช็คครั้งถัดไปให้มังงะที่เพิ่มก่อนมีตัวตั้งเวลา นับจากเวลาที่เช็คล่าสุด
+    // เรื่องที่ไม่เคยเช็คจะถึงเวลาเช็คทันที คืนจำนวนเรื่องที่ตั้งเวลาให้
+    pub async fn schedule_unscheduled() -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = mongodb::bson::DateTime::from(std::time::SystemTime::now());
+        let mut cursor = collection
+            .find(doc! { "next_check_at": { "$exists": false } })
+            .await?;
+
+        let mut scheduled = 0;
+        while let Some(manga) = cursor.try_next().await? {
+            let next_check_at = match manga.last_checked_at {
+                Some(last_checked_at) => mongodb::bson::DateTime::from_millis(
+                    last_checked_at.timestamp_millis() + manga.check_interval * 1000,
+                ),
+                None => now,
+            };
+            Self::reschedule(&manga.url, next_check_at).await?;
+            scheduled += 1;
+        }
+        Ok(scheduled)
     }
 
     pub async fn update(manga: &Manga) -> Result<(), mongodb::error::Error> {
@@ -30,6 +367,7 @@ impl MangaService {
         let update_doc = doc! {
             "$set": {
                 "title": &manga.title,
+                "alt_titles": &manga.alt_titles,
                 "latest_chapter": &manga.latest_chapter,
                 "latest_chapter_url": &manga.latest_chapter_url,
                 "image_url": &manga.image_url,
@@ -38,7 +376,7 @@ impl MangaService {
         };
 
         collection
-            .update_one(doc! { "url": &manga.url }, update_doc)
+            .update_one(url_filter(&manga.url), update_doc)
             .await?;
         Ok(())
     }
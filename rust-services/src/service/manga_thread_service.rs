@@ -0,0 +1,130 @@
+use crate::models::manga_thread::MangaThread;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+pub struct MangaThreadService;
+
+#[allow(dead_code)]
+impl MangaThreadService {
+    pub async fn get_collection() -> mongodb::Collection<MangaThread> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<MangaThread>("manga_threads")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "channel_id": 1, "manga_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "manga_id": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        channel_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<Option<MangaThread>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .find_one(doc! { "channel_id": channel_id, "manga_id": manga_id })
+            .await
+    }
+
+    // บันทึกเธรดของมังงะในช่อง ถ้าเคยมีเธรดเดิมจะเปลี่ยนเป็นเธรดใหม่
+    pub async fn save(thread: &MangaThread) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = DateTime::from(std::time::SystemTime::now());
+
+        collection
+            .update_one(
+                doc! { "channel_id": &thread.channel_id, "manga_id": thread.manga_id },
+                doc! {
+                    "$set": {
+                        "guild_id": &thread.guild_id,
+                        "thread_id": &thread.thread_id,
+                        "updated_at": now
+                    },
+                    "$setOnInsert": { "created_at": now }
+                },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_by_manga(
+        manga_id: &ObjectId,
+    ) -> Result<Vec<MangaThread>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": manga_id }).await?;
+        let mut threads = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            threads.push(doc);
+        }
+        Ok(threads)
+    }
+
+    // ลบเธรดที่บันทึกไว้ทั้งหมดของช่องที่ถูกลบออกจากระบบ
+    pub async fn delete_by_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบเธรดที่บันทึกไว้ของมังงะที่เซิร์ฟเวอร์เลิกติดตาม
+    pub async fn delete_by_guild_manga(
+        guild_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "guild_id": guild_id, "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบเธรดที่บันทึกไว้ทั้งหมดของมังงะที่ถูกลบออกจากระบบ
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ย้ายเธรดไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    // ถ้าช่องมีเธรดของทั้งสองเรื่อง จะใช้เธรดของเรื่องปลายทาง
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        for thread in Self::get_by_manga(from_manga_id).await? {
+            if Self::get(&thread.channel_id, to_manga_id).await?.is_none() {
+                Self::save(&MangaThread {
+                    manga_id: *to_manga_id,
+                    ..thread
+                })
+                .await?;
+            }
+        }
+        Self::delete_by_manga(from_manga_id).await?;
+        Ok(())
+    }
+}
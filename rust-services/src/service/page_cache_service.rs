@@ -0,0 +1,53 @@
+use crate::models::page_cache::CachedPage;
+use crate::utils::mongo;
+use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use std::time::Duration;
+
+// หน้าเว็บที่ไม่ได้ดาวน์โหลดซ้ำนานเกินนี้จะถูกลบออกโดย TTL index
+const PAGE_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub struct PageCacheService;
+
+impl PageCacheService {
+    pub async fn get_collection() -> mongodb::Collection<CachedPage> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<CachedPage>("page_cache")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "updated_at": 1 })
+                    .options(IndexOptions::builder().expire_after(PAGE_CACHE_TTL).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(url: &str) -> Result<Option<CachedPage>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection.find_one(doc! { "_id": url }).await
+    }
+
+    pub async fn save(page: &CachedPage) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .replace_one(doc! { "_id": &page.url }, page)
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(url: &str) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection.delete_one(doc! { "_id": url }).await?;
+        Ok(())
+    }
+}
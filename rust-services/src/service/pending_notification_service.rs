@@ -0,0 +1,113 @@
+use crate::models::pending_notification::PendingNotification;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::IndexModel;
+
+pub struct PendingNotificationService;
+
+#[allow(dead_code)]
+impl PendingNotificationService {
+    pub async fn get_collection() -> mongodb::Collection<PendingNotification> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<PendingNotification>("pending_notifications")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "channel_id": 1, "created_at": 1 })
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn enqueue(
+        notifications: &[PendingNotification],
+    ) -> Result<(), mongodb::error::Error> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+        let collection = Self::get_collection().await;
+        collection.insert_many(notifications).await?;
+        Ok(())
+    }
+
+    // ตอนที่รอส่งของช่องนี้ เรียงตามเวลาที่เข้าคิว
+    pub async fn get_by_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<Vec<PendingNotification>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .sort(doc! { "created_at": 1 })
+            .await?;
+        let mut notifications = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            notifications.push(doc);
+        }
+        Ok(notifications)
+    }
+
+    pub async fn delete_by_ids(ids: &[ObjectId]) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "_id": { "$in": ids } })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบตอนที่รอส่งของช่องที่ถูกลบออกจากระบบ
+    pub async fn delete_by_channel(
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "guild_id": guild_id, "channel_id": channel_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบตอนที่รอส่งของมังงะที่เซิร์ฟเวอร์เลิกติดตาม
+    pub async fn delete_by_guild_manga(
+        guild_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "guild_id": guild_id, "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ลบตอนที่รอส่งของมังงะที่ถูกลบออกจากระบบ
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ย้ายตอนที่รอส่งไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .update_many(
+                doc! { "manga_id": from_manga_id },
+                doc! { "$set": { "manga_id": to_manga_id } },
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::models::chapter_number::ChapterNumber;
+use crate::models::reading_progress::ReadingProgress;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+pub struct ReadingProgressService;
+
+#[allow(dead_code)]
+impl ReadingProgressService {
+    pub async fn get_collection() -> mongodb::Collection<ReadingProgress> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<ReadingProgress>("reading_progress")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "manga_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        user_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<Option<ReadingProgress>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .find_one(doc! { "user_id": user_id, "manga_id": manga_id })
+            .await
+    }
+
+    pub async fn get_by_user(user_id: &str) -> Result<Vec<ReadingProgress>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "manga_title": 1 })
+            .await?;
+        let mut progress = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            progress.push(doc);
+        }
+        Ok(progress)
+    }
+
+    // บันทึกว่าผู้ใช้อ่านถึงตอนนี้แล้ว ถ้าเคยอ่านถึงตอนที่ใหม่กว่าจะไม่ย้อนกลับ
+    // คืนตอนล่าสุดที่อ่านหลังบันทึก
+    pub async fn mark_read(
+        user_id: &str,
+        manga_id: &ObjectId,
+        manga_title: &str,
+        chapter: &ChapterNumber,
+    ) -> Result<ChapterNumber, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        match Self::get(user_id, manga_id).await? {
+            Some(progress) if progress.last_read_chapter >= *chapter => {
+                Ok(progress.last_read_chapter)
+            }
+            Some(_) => {
+                let now = DateTime::from(std::time::SystemTime::now());
+                collection
+                    .update_one(
+                        doc! { "user_id": user_id, "manga_id": manga_id },
+                        doc! {
+                            "$set": {
+                                "manga_title": manga_title,
+                                "last_read_chapter": chapter,
+                                "updated_at": now
+                            }
+                        },
+                    )
+                    .await?;
+                Ok(chapter.clone())
+            }
+            None => {
+                let progress = ReadingProgress::new(
+                    user_id.to_string(),
+                    *manga_id,
+                    manga_title.to_string(),
+                    chapter.clone(),
+                );
+                match collection.insert_one(&progress).await {
+                    Ok(_) => Ok(chapter.clone()),
+                    // กดปุ่มซ้ำพร้อมกัน อีกครั้งบันทึกไปแล้ว
+                    Err(e) if mongo::is_duplicate_key_error(&e) => Ok(chapter.clone()),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+
+    // ลบความคืบหน้าทั้งหมดของมังงะที่ถูกลบออกจากระบบ
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ย้ายความคืบหน้าไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": from_manga_id }).await?;
+        while let Some(progress) = cursor.try_next().await? {
+            Self::mark_read(
+                &progress.user_id,
+                to_manga_id,
+                &progress.manga_title,
+                &progress.last_read_chapter,
+            )
+            .await?;
+        }
+        Self::delete_by_manga(from_manga_id).await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::models::user::User;
 use crate::utils::mongo;
 use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
 
 pub struct UserService;
 
@@ -11,6 +13,21 @@ impl UserService {
         db_pool.collection::<User>("users")
     }
 
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let users_collection: mongodb::Collection<User> = Self::get_collection().await;
+
+        users_collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "guild_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
     // ลงทะเบียนผู้ใช้ใหม่
     pub async fn register_user(
         guild_id: &str,
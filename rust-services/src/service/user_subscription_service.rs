@@ -0,0 +1,148 @@
+use crate::models::user_subscription::UserSubscription;
+use crate::utils::mongo;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+pub struct UserSubscriptionService;
+
+#[allow(dead_code)]
+impl UserSubscriptionService {
+    pub async fn get_collection() -> mongodb::Collection<UserSubscription> {
+        let db_pool: &'static mongo::MongoPool = mongo::get_pool().await;
+        db_pool.collection::<UserSubscription>("user_subscriptions")
+    }
+
+    // สร้าง index ที่ต้องใช้ เรียกครั้งเดียวตอนเริ่มโปรแกรม
+    pub async fn create_indexes() -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+
+        collection
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "manga_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        collection
+            .create_index(IndexModel::builder().keys(doc! { "manga_id": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    // ติดตามมังงะ คืน false ถ้าเคยติดตามไว้แล้ว
+    pub async fn subscribe(subscription: &UserSubscription) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        match collection.insert_one(subscription).await {
+            Ok(_) => Ok(true),
+            Err(e) if mongo::is_duplicate_key_error(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // เลิกติดตามมังงะ คืน true ถ้ามีการติดตามถูกลบ
+    pub async fn unsubscribe(
+        user_id: &str,
+        manga_id: &ObjectId,
+    ) -> Result<bool, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_one(doc! { "user_id": user_id, "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn get_by_user(
+        user_id: &str,
+    ) -> Result<Vec<UserSubscription>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "manga_title": 1 })
+            .await?;
+        let mut subscriptions = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            subscriptions.push(doc);
+        }
+        Ok(subscriptions)
+    }
+
+    // ผู้ใช้ที่ติดตามมังงะเรื่องนี้และยังเปิดรับ DM อยู่
+    pub async fn get_active_by_manga(
+        manga_id: &ObjectId,
+    ) -> Result<Vec<UserSubscription>, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection
+            .find(doc! { "manga_id": manga_id, "dm_closed_at": null })
+            .await?;
+        let mut subscriptions = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            subscriptions.push(doc);
+        }
+        Ok(subscriptions)
+    }
+
+    // หยุดส่ง DM ให้ผู้ใช้ที่ปิดรับ DM
+    pub async fn mark_dm_closed(user_id: &str) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = DateTime::from(std::time::SystemTime::now());
+        collection
+            .update_many(
+                doc! { "user_id": user_id },
+                doc! { "$set": { "dm_closed_at": now, "updated_at": now } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // กลับมาส่ง DM ให้ผู้ใช้อีกครั้ง หลังจากส่ง DM หาผู้ใช้ได้สำเร็จ
+    pub async fn mark_dm_open(user_id: &str) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let now = DateTime::from(std::time::SystemTime::now());
+        collection
+            .update_many(
+                doc! { "user_id": user_id, "dm_closed_at": { "$ne": null } },
+                doc! { "$set": { "dm_closed_at": null, "updated_at": now } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // ลบการติดตามทั้งหมดของมังงะที่ถูกลบออกจากระบบ
+    // จำนวนผู้ใช้ที่ติดตามมังงะเรื่องนี้ทาง DM
+    pub async fn count_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        collection
+            .count_documents(doc! { "manga_id": manga_id })
+            .await
+    }
+
+    pub async fn delete_by_manga(manga_id: &ObjectId) -> Result<u64, mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let result = collection
+            .delete_many(doc! { "manga_id": manga_id })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    // ย้ายการติดตามไปเป็นของมังงะอีกเรื่อง ใช้ตอนรวมมังงะที่ซ้ำกัน
+    pub async fn move_to_manga(
+        from_manga_id: &ObjectId,
+        to_manga_id: &ObjectId,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = Self::get_collection().await;
+        let mut cursor = collection.find(doc! { "manga_id": from_manga_id }).await?;
+        while let Some(subscription) = cursor.try_next().await? {
+            Self::subscribe(&UserSubscription {
+                id: None,
+                manga_id: *to_manga_id,
+                ..subscription
+            })
+            .await?;
+        }
+        Self::delete_by_manga(from_manga_id).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,387 @@
+pub mod executor;
+pub mod http;
+pub mod sing_manga;
+pub mod site_definition;
+
+use crate::models::chapter_number::ChapterNumber;
+use crate::models::page_cache::CachedPage;
+use crate::sources::site_definition::{DeclarativeSource, SiteConfig};
+use reqwest::header::{
+    HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serenity::async_trait;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+pub type ScrapeError = Box<dyn std::error::Error + Send + Sync>;
+
+// จำนวนครั้งที่ลองดาวน์โหลดใหม่เมื่อเกิดข้อผิดพลาดชั่วคราว และระยะรอก่อนลองใหม่ครั้งแรก (เพิ่มเท่าตัวทุกครั้ง)
+const MAX_FETCH_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+// ถ้าเว็บไซต์ขอให้รอนานกว่านี้ (Retry-After) จะไม่รอ แต่ให้ตัวตั้งเวลาเลื่อนการเช็คออกไปแทน
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(120);
+
+// ข้อผิดพลาดจากการดาวน์โหลดหน้าเว็บ
+#[derive(Debug)]
+pub enum FetchError {
+    // เว็บไซต์ตอบกลับด้วยสถานะที่ไม่สำเร็จ
+    Status {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    // ส่งคำขอหรืออ่านข้อมูลไม่สำเร็จ (เช่น หมดเวลา เชื่อมต่อไม่ได้)
+    Request(reqwest::Error),
+}
+
+impl FetchError {
+    // ข้อผิดพลาดที่ลองใหม่แล้วมีโอกาสสำเร็จ
+    pub fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            // is_request() รวมถึง URL ที่ไม่ถูกต้องซึ่งลองใหม่ก็ไม่สำเร็จ จึงนับเฉพาะการเชื่อมต่อที่ขาดหรือช้า
+            FetchError::Request(e) => {
+                e.is_timeout() || e.is_connect() || e.is_body() || e.is_decode()
+            }
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            FetchError::Status { retry_after, .. } => *retry_after,
+            FetchError::Request(_) => None,
+        }
+    }
+}
+
+// ระยะเวลาที่เว็บไซต์ขอให้รอ (Retry-After) ถ้าการดึงข้อมูลไม่สำเร็จเพราะดาวน์โหลดไม่ได้
+pub fn retry_after(error: &ScrapeError) -> Option<Duration> {
+    error.downcast_ref::<FetchError>()?.retry_after()
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Status { status, .. } => write!(f, "เว็บไซต์ตอบกลับด้วยสถานะ {status}"),
+            FetchError::Request(e) => write!(f, "ส่งคำขอไม่สำเร็จ: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        FetchError::Request(e)
+    }
+}
+
+// อะแดปเตอร์สำหรับดึงข้อมูลมังงะจากเว็บไซต์หนึ่ง ๆ
+// การเพิ่มเว็บไซต์ใหม่ทำได้โดยเพิ่มโมดูลที่ implement trait นี้ แล้วลงทะเบียนใน SourceRegistry::load
+// หรือเพิ่มนิยามเว็บไซต์ในไฟล์ตั้งค่า (ดู sources.example.toml)
+#[async_trait]
+pub trait MangaSource: Send + Sync {
+    // ชื่อของแหล่งที่มา ใช้สำหรับแสดงผลและ log
+    fn name(&self) -> &str;
+
+    // ตรวจสอบว่าอะแดปเตอร์นี้รองรับ host ที่ระบุหรือไม่ (host ตัด www. ออกแล้ว)
+    fn supports_host(&self, host: &str) -> bool;
+
+    // ดึงข้อมูลเรื่องและรายการตอนทั้งหมดจากหน้าเว็บของมังงะ
+    async fn scrape(&self, url: &str) -> Result<ScrapeResult, ScrapeError>;
+}
+
+// ผลลัพธ์จากการดึงข้อมูลหน้าเว็บของมังงะหนึ่งเรื่อง
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScrapeResult {
+    pub title: String,
+    // ชื่ออื่นของเรื่อง (ชื่อภาษาอื่น ชื่อย่อ)
+    pub alt_titles: Vec<String>,
+    pub cover_url: Option<String>,
+    pub status: Option<String>,
+    // เรียงจากตอนเก่าไปใหม่
+    pub chapters: Vec<ScrapedChapter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScrapedChapter {
+    pub number: ChapterNumber,
+    pub title: String,
+    pub url: String,
+    // วันที่ปล่อยตอนตามที่แสดงบนเว็บไซต์ (ถ้ามี)
+    pub release_date: Option<String>,
+}
+
+impl ScrapeResult {
+    pub fn latest_chapter(&self) -> Option<&ScrapedChapter> {
+        self.chapters.last()
+    }
+}
+
+// ทะเบียนของอะแดปเตอร์ทั้งหมด เลือกอะแดปเตอร์ตาม host ของ URL
+// เว็บไซต์จากไฟล์ตั้งค่าถูกค้นหาก่อน จึงใช้แก้ selector ของอะแดปเตอร์ในโค้ดได้โดยไม่ต้อง build ใหม่
+pub struct SourceRegistry {
+    sources: Vec<Arc<dyn MangaSource>>,
+    // เปลี่ยนเมื่อไฟล์ตั้งค่าหรือเวอร์ชันของโปรแกรมเปลี่ยน ใช้บอกว่าผลที่แปลงเก็บไว้ยังใช้ได้หรือไม่
+    fingerprint: String,
+}
+
+impl SourceRegistry {
+    fn builtin() -> Vec<Arc<dyn MangaSource>> {
+        vec![Arc::new(sing_manga::SingManga::new())]
+    }
+
+    // สร้างทะเบียนจากไฟล์ตั้งค่า ถ้าไม่มีไฟล์จะใช้เฉพาะอะแดปเตอร์ในโค้ด
+    // ใช้ตอนเริ่มโปรแกรมเท่านั้น ระหว่างทำงานให้ใช้ load_async เพื่อไม่ให้บล็อก runtime
+    fn load() -> Result<Self, ScrapeError> {
+        let path = config_path();
+        let text = config_text(&path, std::fs::read_to_string(&path))?;
+        Self::from_config(&path, text)
+    }
+
+    async fn load_async() -> Result<Self, ScrapeError> {
+        let path = config_path();
+        let text = config_text(&path, tokio::fs::read_to_string(&path).await)?;
+        Self::from_config(&path, text)
+    }
+
+    fn from_config(path: &str, text: String) -> Result<Self, ScrapeError> {
+        let config = if text.is_empty() {
+            SiteConfig::default()
+        } else {
+            SiteConfig::from_toml(&text).map_err(|e| format!("อ่านไฟล์ตั้งค่า {path} ไม่สำเร็จ: {e}"))?
+        };
+
+        let mut sources: Vec<Arc<dyn MangaSource>> = Vec::new();
+        for definition in config.sites {
+            sources.push(Arc::new(DeclarativeSource::from_definition(definition)?));
+        }
+        sources.extend(Self::builtin());
+
+        Ok(SourceRegistry {
+            sources,
+            fingerprint: Self::fingerprint_of(&text),
+        })
+    }
+
+    fn fingerprint_of(config: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        config.hash(&mut hasher);
+        format!("{}-{:016x}", env!("CARGO_PKG_VERSION"), hasher.finish())
+    }
+
+    pub fn find_by_url(&self, url: &str) -> Option<Arc<dyn MangaSource>> {
+        let host = host_of(url)?;
+        self.sources
+            .iter()
+            .find(|source| source.supports_host(&host))
+            .cloned()
+    }
+
+    pub fn source_names(&self) -> Vec<String> {
+        self.sources
+            .iter()
+            .map(|source| source.name().to_string())
+            .collect()
+    }
+}
+
+fn config_path() -> String {
+    std::env::var("MANGA_SOURCES_FILE").unwrap_or_else(|_| "sources.toml".to_string())
+}
+
+// เนื้อหาไฟล์ตั้งค่า ถ้าไม่มีไฟล์จะคืนข้อความว่าง
+fn config_text(path: &str, read: std::io::Result<String>) -> Result<String, ScrapeError> {
+    match read {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("เปิดไฟล์ตั้งค่า {path} ไม่สำเร็จ: {e}").into()),
+    }
+}
+
+static REGISTRY: OnceLock<RwLock<Arc<SourceRegistry>>> = OnceLock::new();
+
+fn registry_cell() -> &'static RwLock<Arc<SourceRegistry>> {
+    REGISTRY.get_or_init(|| {
+        let registry = SourceRegistry::load().unwrap_or_else(|e| {
+            println!("{e} ใช้เฉพาะอะแดปเตอร์ในโค้ดแทน");
+            SourceRegistry {
+                sources: SourceRegistry::builtin(),
+                fingerprint: SourceRegistry::fingerprint_of(""),
+            }
+        });
+        RwLock::new(Arc::new(registry))
+    })
+}
+
+// โหลดทะเบียนครั้งแรกตอนเริ่มโปรแกรม เพื่อให้เห็นข้อผิดพลาดของไฟล์ตั้งค่าทันที
+pub fn init() {
+    let names = registry().source_names();
+    println!("โหลดอะแดปเตอร์มังงะ {} รายการ: {}", names.len(), names.join(", "));
+}
+
+pub fn registry() -> Arc<SourceRegistry> {
+    registry_cell().read().unwrap().clone()
+}
+
+// โหลดไฟล์ตั้งค่าใหม่ ถ้าไฟล์ผิดพลาดจะคงทะเบียนเดิมไว้
+pub async fn reload() -> Result<Arc<SourceRegistry>, ScrapeError> {
+    let registry = Arc::new(SourceRegistry::load_async().await?);
+    *registry_cell().write().unwrap() = registry.clone();
+    Ok(registry)
+}
+
+// ดาวน์โหลดหน้าเว็บแล้วแปลงด้วย parse
+// ถ้าเว็บไซต์ตอบว่าหน้าเว็บไม่เปลี่ยนตั้งแต่ครั้งก่อน จะคืนผลที่แปลงเก็บไว้โดยไม่แปลงใหม่
+pub async fn fetch_and_parse(
+    url: &str,
+    parse: impl FnOnce(&str) -> Result<ScrapeResult, ScrapeError>,
+) -> Result<ScrapeResult, ScrapeError> {
+    let parser = registry().fingerprint.clone();
+    let stored = http::cached_page(url).await;
+    let had_stored = stored.is_some();
+    let cached = stored.filter(|page| page.parser == parser);
+
+    let Some(page) = fetch_html(url, cached.as_ref()).await? else {
+        return cached
+            .map(|page| page.result)
+            .ok_or_else(|| format!("เว็บไซต์ตอบ 304 แต่ไม่มีหน้าเว็บที่เก็บไว้: {url}").into());
+    };
+
+    let result = parse(&page.body)?;
+    if page.etag.is_some() || page.last_modified.is_some() {
+        http::store_page(CachedPage {
+            url: url.to_string(),
+            etag: page.etag,
+            last_modified: page.last_modified,
+            parser,
+            result: result.clone(),
+            updated_at: mongodb::bson::DateTime::from(std::time::SystemTime::now()),
+        })
+        .await;
+    } else if had_stored {
+        http::forget_page(url).await;
+    }
+    Ok(result)
+}
+
+// หน้าเว็บที่ดาวน์โหลดมา พร้อม ETag และ Last-Modified (ถ้ามี)
+struct DownloadedPage {
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+// ดาวน์โหลด HTML ของหน้าเว็บ ผ่านตัวควบคุมจำนวนคำขอและอัตราการส่งต่อ host
+// ข้อผิดพลาดชั่วคราว (หมดเวลา 5xx 429) จะลองใหม่โดยรอนานขึ้นเท่าตัวทุกครั้ง หรือตาม Retry-After
+// คืน None เมื่อหน้าเว็บไม่เปลี่ยนจาก cached
+async fn fetch_html(
+    url: &str,
+    cached: Option<&CachedPage>,
+) -> Result<Option<DownloadedPage>, ScrapeError> {
+    let host = host_of(url).ok_or_else(|| format!("URL ไม่ถูกต้อง: {url}"))?;
+
+    let mut attempt = 0;
+    loop {
+        let result = {
+            let _permit = executor::executor().acquire(&host).await;
+            fetch_once(url, cached).await
+        };
+        let error = match result {
+            Ok(page) => return Ok(page),
+            Err(e) if e.is_transient() && attempt < MAX_FETCH_RETRIES => e,
+            Err(e) => return Err(e.into()),
+        };
+
+        let delay = error
+            .retry_after()
+            .unwrap_or(RETRY_BASE_DELAY * 2u32.pow(attempt));
+        if delay > MAX_RETRY_DELAY {
+            return Err(error.into());
+        }
+        attempt += 1;
+        println!(
+            "ดาวน์โหลด {url} ไม่สำเร็จ ({error}) ลองใหม่ครั้งที่ {attempt} ใน {} วินาที",
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// ถ้าเคยดาวน์โหลดหน้านี้ไว้จะส่ง If-None-Match / If-Modified-Since ไปด้วย
+// เว็บไซต์ที่ตอบ 304 (ไม่มีการเปลี่ยนแปลง) จะคืน None
+async fn fetch_once(
+    url: &str,
+    cached: Option<&CachedPage>,
+) -> Result<Option<DownloadedPage>, FetchError> {
+    let mut request = http::client().get(url);
+    if let Some(page) = cached {
+        if let Some(etag) = &page.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &page.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let response = request.send().await?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(FetchError::Status {
+            status,
+            retry_after: response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after),
+        });
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body = response.text().await?;
+    Ok(Some(DownloadedPage {
+        body,
+        etag,
+        last_modified,
+    }))
+}
+
+// Retry-After เป็นได้ทั้งจำนวนวินาทีและวันเวลาแบบ HTTP
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+        .or(Some(Duration::ZERO))
+}
+
+// ดึง host ของ URL แบบตัวพิมพ์เล็กและตัด www. ออก
+pub fn host_of(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+// เช็คว่า host ตรงกับโดเมนที่กำหนด หรือเป็น subdomain ของโดเมนนั้น
+pub fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
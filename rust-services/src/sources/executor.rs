@@ -0,0 +1,176 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+// จำนวนคำขอไปยังเว็บไซต์ที่ทำพร้อมกันได้สูงสุดทั้งระบบ
+pub const MAX_CONCURRENT_SCRAPES: usize = 4;
+// จำนวนคำขอที่ส่งติดกันได้ทันทีต่อ host และระยะเวลาที่ได้สิทธิ์ส่งเพิ่มหนึ่งครั้ง
+const HOST_BURST: f64 = 2.0;
+const HOST_REFILL_INTERVAL: Duration = Duration::from_secs(5);
+// หน่วงเวลาแบบสุ่มเพิ่มก่อนส่งคำขอ ไม่ให้คำขอไปถึงเว็บไซต์ในจังหวะเดียวกันทุกครั้ง
+const MAX_JITTER: Duration = Duration::from_millis(1500);
+
+// ถังสิทธิ์ส่งคำขอของ host หนึ่ง ค่าติดลบหมายถึงมีคำขอจองสิทธิ์รอไว้แล้ว
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(now: Instant) -> Self {
+        TokenBucket {
+            tokens: HOST_BURST,
+            updated_at: now,
+        }
+    }
+
+    // จองสิทธิ์หนึ่งครั้ง คืนระยะเวลาที่ต้องรอก่อนจะได้ใช้สิทธิ์นั้น
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed / HOST_REFILL_INTERVAL.as_secs_f64()).min(HOST_BURST);
+        self.updated_at = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            HOST_REFILL_INTERVAL.mul_f64(-self.tokens)
+        }
+    }
+}
+
+// ตัวควบคุมการดึงข้อมูลที่ทุกอะแดปเตอร์ใช้ร่วมกัน
+// จำกัดจำนวนคำขอพร้อมกันทั้งระบบ และจำกัดอัตราการส่งคำขอของแต่ละ host
+pub struct ScrapeExecutor {
+    slots: Semaphore,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl ScrapeExecutor {
+    fn new() -> Self {
+        ScrapeExecutor {
+            slots: Semaphore::new(MAX_CONCURRENT_SCRAPES),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // รอจนกว่าจะส่งคำขอไปยัง host นี้ได้ ต้องถือ permit ที่ได้ไว้จนกว่าคำขอจะเสร็จ
+    // จองช่องของทั้งระบบก่อนแล้วจึงจองสิทธิ์ของ host ถ้าจองสิทธิ์ไว้ก่อนแล้วไปรอช่องนาน
+    // คำขอที่จองไว้หลายรายการจะถูกส่งไปยัง host เดียวกันติดกันทันทีที่มีช่องว่าง ซึ่งเกินอัตราที่กำหนด
+    pub async fn acquire(&self, host: &str) -> SemaphorePermit<'_> {
+        // Semaphore ของ tokio ให้สิทธิ์ตามลำดับที่รอ
+        let permit = self
+            .slots
+            .acquire()
+            .await
+            .expect("semaphore ของการดึงข้อมูลไม่ถูกปิด");
+
+        let wait = {
+            let now = Instant::now();
+            let mut buckets = self.buckets.lock().unwrap();
+            buckets
+                .entry(host.to_string())
+                .or_insert_with(|| TokenBucket::new(now))
+                .reserve(now)
+        };
+        tokio::time::sleep(wait + jitter()).await;
+        permit
+    }
+}
+
+fn jitter() -> Duration {
+    let random = RandomState::new().build_hasher().finish();
+    MAX_JITTER.mul_f64((random % 1000) as f64 / 1000.0)
+}
+
+static EXECUTOR: OnceLock<ScrapeExecutor> = OnceLock::new();
+
+pub fn executor() -> &'static ScrapeExecutor {
+    EXECUTOR.get_or_init(ScrapeExecutor::new)
+}
+
+// เรียงงานสลับกันทีละ host (round-robin) ตามลำดับเดิมภายใน host เดียวกัน
+// เพื่อไม่ให้ host ที่มีเรื่องเยอะกินรอบการเช็คของ host อื่นจนหมด
+pub fn interleave_by_host<T>(items: Vec<T>, host_of: impl Fn(&T) -> String) -> Vec<T> {
+    let mut queues: Vec<(String, Vec<T>)> = Vec::new();
+    for item in items {
+        let host = host_of(&item);
+        match queues.iter_mut().find(|(h, _)| *h == host) {
+            Some((_, queue)) => queue.push(item),
+            None => queues.push((host, vec![item])),
+        }
+    }
+
+    let mut queues: Vec<std::vec::IntoIter<T>> = queues
+        .into_iter()
+        .map(|(_, queue)| queue.into_iter())
+        .collect();
+    let mut interleaved = Vec::new();
+    loop {
+        let before = interleaved.len();
+        for queue in queues.iter_mut() {
+            if let Some(item) = queue.next() {
+                interleaved.push(item);
+            }
+        }
+        if interleaved.len() == before {
+            return interleaved;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_allows_burst_then_waits_for_refill() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(start);
+
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), HOST_REFILL_INTERVAL);
+        // คำขอที่จองต่อจากคิวเดิมต้องรอต่อท้ายอีกหนึ่งช่วง
+        assert_eq!(bucket.reserve(start), HOST_REFILL_INTERVAL * 2);
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time_up_to_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(start);
+        bucket.reserve(start);
+        bucket.reserve(start);
+
+        let later = start + HOST_REFILL_INTERVAL;
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), HOST_REFILL_INTERVAL);
+
+        // ว่างนานแค่ไหนก็สะสมสิทธิ์ได้ไม่เกิน HOST_BURST
+        let idle = later + HOST_REFILL_INTERVAL * 10;
+        assert_eq!(bucket.reserve(idle), Duration::ZERO);
+        assert_eq!(bucket.reserve(idle), Duration::ZERO);
+        assert!(bucket.reserve(idle) > Duration::ZERO);
+    }
+
+    #[test]
+    fn interleave_by_host_alternates_hosts_in_original_order() {
+        let items = vec!["a1", "a2", "a3", "b1", "c1", "b2"];
+        let interleaved = interleave_by_host(items, |item| item[..1].to_string());
+        assert_eq!(interleaved, vec!["a1", "b1", "c1", "a2", "b2", "a3"]);
+    }
+
+    #[test]
+    fn interleave_by_host_handles_empty_and_single_host() {
+        let empty: Vec<&str> = Vec::new();
+        assert!(interleave_by_host(empty, |item| item.to_string()).is_empty());
+
+        let items = vec!["a1", "a2", "a3"];
+        let interleaved = interleave_by_host(items, |item| item[..1].to_string());
+        assert_eq!(interleaved, vec!["a1", "a2", "a3"]);
+    }
+}
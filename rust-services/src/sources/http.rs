@@ -0,0 +1,79 @@
+use crate::models::page_cache::CachedPage;
+use crate::service::page_cache_service::PageCacheService;
+use lru_cache::LruCache;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_LANGUAGE};
+use reqwest::Client;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// จำนวนหน้าเว็บสูงสุดที่เก็บไว้ในหน่วยความจำ เกินแล้วจะลบหน้าที่ไม่ได้ใช้นานที่สุดออก (ยังอยู่ในฐานข้อมูล)
+const MAX_CACHED_PAGES: usize = 1000;
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+// HTTP client เดียวที่ทุกอะแดปเตอร์ใช้ร่วมกัน ใช้การเชื่อมต่อและ cookie ของแต่ละเว็บไซต์ซ้ำ
+// และขอข้อมูลแบบบีบอัด (br, gzip, deflate) ซึ่ง reqwest คลายให้อัตโนมัติ
+pub fn client() -> &'static Client {
+    CLIENT.get_or_init(|| {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static(
+                "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8",
+            ),
+        );
+        headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
+
+        Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .default_headers(headers)
+            .cookie_store(true)
+            .build()
+            .expect("สร้าง HTTP client ไม่สำเร็จ")
+    })
+}
+
+static PAGES: OnceLock<Mutex<LruCache<String, CachedPage>>> = OnceLock::new();
+
+fn pages() -> &'static Mutex<LruCache<String, CachedPage>> {
+    PAGES.get_or_init(|| Mutex::new(LruCache::new(MAX_CACHED_PAGES)))
+}
+
+// หน้าเว็บที่เก็บไว้ ดูในหน่วยความจำก่อน ถ้าไม่มีจะโหลดจากฐานข้อมูล
+pub async fn cached_page(url: &str) -> Option<CachedPage> {
+    let cached = pages().lock().unwrap().get_mut(url).cloned();
+    if cached.is_some() {
+        return cached;
+    }
+
+    let page = match PageCacheService::get(url).await {
+        Ok(page) => page?,
+        Err(e) => {
+            println!("เกิดข้อผิดพลาดในการดึงหน้าเว็บที่เก็บไว้ของ {url}: {e:?}");
+            return None;
+        }
+    };
+    pages()
+        .lock()
+        .unwrap()
+        .insert(url.to_string(), page.clone());
+    Some(page)
+}
+
+pub async fn store_page(page: CachedPage) {
+    if let Err(e) = PageCacheService::save(&page).await {
+        println!("เกิดข้อผิดพลาดในการเก็บหน้าเว็บ {}: {e:?}", page.url);
+    }
+    pages().lock().unwrap().insert(page.url.clone(), page);
+}
+
+// ลบหน้าเว็บที่เก็บไว้ ใช้เมื่อเว็บไซต์เลิกส่ง ETag และ Last-Modified
+pub async fn forget_page(url: &str) {
+    pages().lock().unwrap().remove(url);
+    if let Err(e) = PageCacheService::delete(url).await {
+        println!("เกิดข้อผิดพลาดในการลบหน้าเว็บที่เก็บไว้ของ {url}: {e:?}");
+    }
+}
@@ -0,0 +1,53 @@
+use crate::sources::site_definition::{DeclarativeSource, SiteDefinition, SiteSelectors};
+use crate::sources::{fetch_and_parse, host_matches, MangaSource, ScrapeError, ScrapeResult};
+use serenity::async_trait;
+
+// อะแดปเตอร์สำหรับ sing-manga.com
+// ใช้ตัว parse เดียวกับเว็บไซต์ที่กำหนดในไฟล์ตั้งค่า แต่ฝัง selector ไว้ในโค้ด
+// เพื่อให้ใช้งานได้แม้ไม่มีไฟล์ตั้งค่า
+pub struct SingManga {
+    parser: DeclarativeSource,
+}
+
+impl SingManga {
+    pub fn new() -> Self {
+        let definition = SiteDefinition {
+            name: "sing-manga".to_string(),
+            hosts: vec!["sing-manga.com".to_string()],
+            selectors: SiteSelectors {
+                title: "h1.entry-title".to_string(),
+                alt_titles: Some("div.seriestualt".to_string()),
+                cover: "div.thumb img".to_string(),
+                cover_attribute: "src".to_string(),
+                status: Some("div.tsinfo div.imptdt i".to_string()),
+                chapter_list: "#chapterlist li".to_string(),
+                chapter_link: Some("a".to_string()),
+                chapter_number: Some("span.chapternum".to_string()),
+                chapter_title: None,
+                chapter_date: Some("span.chapterdate".to_string()),
+            },
+            chapter_number_regex: None,
+            newest_first: true,
+        };
+
+        SingManga {
+            parser: DeclarativeSource::from_definition(definition)
+                .expect("selector ของ sing-manga ต้องถูกต้องเสมอ"),
+        }
+    }
+}
+
+#[async_trait]
+impl MangaSource for SingManga {
+    fn name(&self) -> &str {
+        "sing-manga"
+    }
+
+    fn supports_host(&self, host: &str) -> bool {
+        host_matches(host, "sing-manga.com")
+    }
+
+    async fn scrape(&self, url: &str) -> Result<ScrapeResult, ScrapeError> {
+        fetch_and_parse(url, |html| self.parser.parse(url, html)).await
+    }
+}
@@ -0,0 +1,261 @@
+use crate::models::chapter_number::ChapterNumber;
+use crate::sources::{
+    fetch_and_parse, host_matches, MangaSource, ScrapeError, ScrapeResult, ScrapedChapter,
+};
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serenity::async_trait;
+
+// ไฟล์ตั้งค่าเว็บไซต์ ประกอบด้วยหลายเว็บไซต์ในรูปแบบ [[site]]
+#[derive(Deserialize, Debug, Default)]
+pub struct SiteConfig {
+    #[serde(default, rename = "site")]
+    pub sites: Vec<SiteDefinition>,
+}
+
+// นิยามของเว็บไซต์แบบ WordPress/Madara ที่อธิบายด้วย CSS selector
+#[derive(Deserialize, Debug, Clone)]
+pub struct SiteDefinition {
+    pub name: String,
+    pub hosts: Vec<String>,
+    pub selectors: SiteSelectors,
+    // regex สำหรับตัดเฉพาะส่วนที่เป็นเลขตอนออกจากข้อความ ใช้ capture group แรกถ้ามี
+    // ถ้าไม่ระบุจะใช้ข้อความทั้งหมด
+    pub chapter_number_regex: Option<String>,
+    // true ถ้ารายการตอนเรียงจากใหม่ไปเก่า (เช่น Madara)
+    #[serde(default)]
+    pub newest_first: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SiteSelectors {
+    pub title: String,
+    // ชื่ออื่นของเรื่อง คั่นด้วย , / ; หรือ |
+    pub alt_titles: Option<String>,
+    pub cover: String,
+    #[serde(default = "default_cover_attribute")]
+    pub cover_attribute: String,
+    // สถานะของเรื่อง (เช่น Ongoing, Completed)
+    pub status: Option<String>,
+    // แต่ละ element คือหนึ่งตอน
+    pub chapter_list: String,
+    // ลิงก์ของตอนภายใน element ถ้าไม่ระบุจะใช้ href ของ element เอง
+    pub chapter_link: Option<String>,
+    // ข้อความเลขตอนภายใน element ถ้าไม่ระบุจะใช้ข้อความทั้งหมดของ element
+    pub chapter_number: Option<String>,
+    // ชื่อตอนภายใน element ถ้าไม่ระบุจะใช้ข้อความของเลขตอน
+    pub chapter_title: Option<String>,
+    // วันที่ปล่อยตอนภายใน element
+    pub chapter_date: Option<String>,
+}
+
+fn default_cover_attribute() -> String {
+    "src".to_string()
+}
+
+impl SiteConfig {
+    pub fn from_toml(text: &str) -> Result<Self, ScrapeError> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+// อะแดปเตอร์ที่สร้างจาก SiteDefinition โดย compile selector และ regex ไว้ล่วงหน้า
+pub struct DeclarativeSource {
+    name: String,
+    hosts: Vec<String>,
+    title: Selector,
+    alt_titles: Option<Selector>,
+    cover: Selector,
+    cover_attribute: String,
+    status: Option<Selector>,
+    chapter_list: Selector,
+    chapter_link: Option<Selector>,
+    chapter_number: Option<Selector>,
+    chapter_title: Option<Selector>,
+    chapter_date: Option<Selector>,
+    chapter_number_regex: Option<Regex>,
+    newest_first: bool,
+}
+
+fn parse_selector(site: &str, selector: &str) -> Result<Selector, ScrapeError> {
+    Selector::parse(selector)
+        .map_err(|e| format!("selector ของ {site} ไม่ถูกต้อง '{selector}': {e:?}").into())
+}
+
+fn parse_optional_selector(
+    site: &str,
+    selector: Option<&str>,
+) -> Result<Option<Selector>, ScrapeError> {
+    selector.map(|s| parse_selector(site, s)).transpose()
+}
+
+// ข้อความของ element แรกที่ตรงกับ selector โดยตัดช่องว่างหัวท้าย
+fn select_text(element: &ElementRef, selector: &Selector) -> Option<String> {
+    let text = element
+        .select(selector)
+        .next()?
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+impl DeclarativeSource {
+    pub fn from_definition(definition: SiteDefinition) -> Result<Self, ScrapeError> {
+        let name = definition.name;
+        let selectors = definition.selectors;
+
+        Ok(DeclarativeSource {
+            title: parse_selector(&name, &selectors.title)?,
+            alt_titles: parse_optional_selector(&name, selectors.alt_titles.as_deref())?,
+            cover: parse_selector(&name, &selectors.cover)?,
+            cover_attribute: selectors.cover_attribute,
+            status: parse_optional_selector(&name, selectors.status.as_deref())?,
+            chapter_list: parse_selector(&name, &selectors.chapter_list)?,
+            chapter_link: parse_optional_selector(&name, selectors.chapter_link.as_deref())?,
+            chapter_number: parse_optional_selector(&name, selectors.chapter_number.as_deref())?,
+            chapter_title: parse_optional_selector(&name, selectors.chapter_title.as_deref())?,
+            chapter_date: parse_optional_selector(&name, selectors.chapter_date.as_deref())?,
+            chapter_number_regex: definition
+                .chapter_number_regex
+                .as_deref()
+                .map(Regex::new)
+                .transpose()?,
+            newest_first: definition.newest_first,
+            hosts: definition
+                .hosts
+                .into_iter()
+                .map(|host| {
+                    let host = host.to_lowercase();
+                    host.strip_prefix("www.").unwrap_or(&host).to_string()
+                })
+                .collect(),
+            name,
+        })
+    }
+
+    fn parse_chapter_number(&self, text: &str) -> Option<ChapterNumber> {
+        let Some(regex) = &self.chapter_number_regex else {
+            return ChapterNumber::parse(text);
+        };
+        let captures = regex.captures(text)?;
+        let matched = captures.get(1).or_else(|| captures.get(0))?.as_str();
+        ChapterNumber::parse(matched)
+    }
+
+    // ลิงก์ของตอน แปลงลิงก์แบบ relative ให้เป็น URL เต็มตามหน้าเว็บของมังงะ
+    fn chapter_url(&self, base: &Url, element: &ElementRef) -> Option<String> {
+        let link = match &self.chapter_link {
+            Some(selector) => element.select(selector).next()?,
+            None => *element,
+        };
+        let href = link.value().attr("href")?;
+        base.join(href).ok().map(|url| url.to_string())
+    }
+
+    // อ่านข้อมูลหนึ่งตอนจาก element ของรายการตอน คืน None ถ้าอ่านเลขตอนหรือลิงก์ไม่ได้
+    fn parse_chapter(&self, base: &Url, element: &ElementRef) -> Option<ScrapedChapter> {
+        let number_text = match &self.chapter_number {
+            Some(selector) => select_text(element, selector)?,
+            None => element.text().collect::<String>().trim().to_string(),
+        };
+        let number = self.parse_chapter_number(&number_text)?;
+        let url = self.chapter_url(base, element)?;
+        let title = self
+            .chapter_title
+            .as_ref()
+            .and_then(|selector| select_text(element, selector))
+            .unwrap_or(number_text);
+        let release_date = self
+            .chapter_date
+            .as_ref()
+            .and_then(|selector| select_text(element, selector));
+
+        Some(ScrapedChapter {
+            number,
+            title,
+            url,
+            release_date,
+        })
+    }
+
+    pub fn parse(&self, url: &str, html: &str) -> Result<ScrapeResult, ScrapeError> {
+        let base = Url::parse(url)?;
+        let document = Html::parse_document(html);
+        let root = document.root_element();
+
+        let title = select_text(&root, &self.title).ok_or("ไม่พบชื่อการ์ตูน")?;
+
+        let alt_titles = self
+            .alt_titles
+            .as_ref()
+            .and_then(|selector| select_text(&root, selector))
+            .map(|text| {
+                text.split([',', '/', ';', '|', '\n'])
+                    .map(|alt| alt.trim().to_string())
+                    .filter(|alt| !alt.is_empty() && *alt != title)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let cover_url = document.select(&self.cover).next().map(|img| {
+            img.value()
+                .attr(&self.cover_attribute)
+                .unwrap_or_default()
+                .to_string()
+        });
+
+        let status = self
+            .status
+            .as_ref()
+            .and_then(|selector| select_text(&root, selector));
+
+        let mut chapter_elements: Vec<_> = document.select(&self.chapter_list).collect();
+        if chapter_elements.is_empty() {
+            return Err("ไม่พบรายการตอน".into());
+        }
+        if self.newest_first {
+            chapter_elements.reverse();
+        }
+
+        // เรียงจากตอนเก่าไปใหม่ และตัดตอนที่ลิงก์ซ้ำกันออก
+        let mut chapters: Vec<ScrapedChapter> = Vec::new();
+        for element in &chapter_elements {
+            if let Some(chapter) = self.parse_chapter(&base, element) {
+                if !chapters.iter().any(|c| c.url == chapter.url) {
+                    chapters.push(chapter);
+                }
+            }
+        }
+        if chapters.is_empty() {
+            return Err("อ่านหมายเลขตอนไม่ได้".into());
+        }
+        chapters.sort_by(|a, b| a.number.cmp(&b.number));
+
+        Ok(ScrapeResult {
+            title,
+            alt_titles,
+            cover_url,
+            status,
+            chapters,
+        })
+    }
+}
+
+#[async_trait]
+impl MangaSource for DeclarativeSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn supports_host(&self, host: &str) -> bool {
+        self.hosts.iter().any(|domain| host_matches(host, domain))
+    }
+
+    async fn scrape(&self, url: &str) -> Result<ScrapeResult, ScrapeError> {
+        fetch_and_parse(url, |html| self.parse(url, html)).await
+    }
+}
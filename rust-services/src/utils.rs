@@ -1 +1,5 @@
+pub mod fuzzy;
 pub mod mongo;
+pub mod secret;
+pub mod time;
+pub mod url;
@@ -0,0 +1,57 @@
+// คะแนนความใกล้เคียงของคำค้นกับข้อความ (ไม่สนตัวพิมพ์เล็กใหญ่) ยิ่งน้อยยิ่งใกล้
+// 0 = ขึ้นต้นด้วยคำค้น, 1 = ตรงกับต้นคำ, 2 = มีคำค้นอยู่ในข้อความ
+// ตั้งแต่ 10 ขึ้นไป = ตัวอักษรของคำค้นปรากฏตามลำดับ (บวกจำนวนตัวอักษรที่ข้าม)
+// คืน None ถ้าไม่ตรงเลย
+pub fn match_score(query: &str, candidate: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if query.is_empty() || candidate.starts_with(&query) {
+        return Some(0);
+    }
+
+    if let Some(position) = candidate.find(&query) {
+        let at_word_start = candidate[..position].ends_with(|c: char| !c.is_alphanumeric());
+        return Some(if at_word_start { 1 } else { 2 });
+    }
+
+    let mut candidate_chars = candidate.chars();
+    let mut skipped: u32 = 0;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        loop {
+            match candidate_chars.next() {
+                Some(c) if c == query_char => break,
+                Some(_) => skipped += 1,
+                None => return None,
+            }
+        }
+    }
+    Some(10 + skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_prefix_then_word_start_then_substring() {
+        assert_eq!(match_score("one", "One Piece"), Some(0));
+        assert_eq!(match_score("PIECE", "One Piece"), Some(1));
+        assert_eq!(match_score("iece", "One Piece"), Some(2));
+        assert_eq!(match_score("   ", "One Piece"), Some(0));
+    }
+
+    #[test]
+    fn scores_subsequence_by_skipped_characters() {
+        assert_eq!(match_score("opc", "One Piece"), Some(15));
+        // ช่องว่างในคำค้นไม่นับเป็นตัวอักษรที่ต้องตรง
+        assert_eq!(match_score("o p", "One Piece"), Some(13));
+        assert!(match_score("opc", "One Piece") > match_score("iece", "One Piece"));
+    }
+
+    #[test]
+    fn returns_none_without_match() {
+        assert_eq!(match_score("xyz", "One Piece"), None);
+        assert_eq!(match_score("eno", "One Piece"), None);
+    }
+}
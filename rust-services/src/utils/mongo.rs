@@ -1,3 +1,4 @@
+use mongodb::bson::{doc, Document};
 use mongodb::{options::ClientOptions, Client, Collection, Database};
 use std::sync::Arc;
 use std::time::Duration;
@@ -39,9 +40,9 @@ pub async fn init() -> mongodb::error::Result<()> {
     match client.list_database_names().await {
         Ok(_) => println!("ทดสอบเชื่อมต่อ MongoDB สำเร็จ"),
         Err(e) => {
-            eprintln!("ไม่สามารถเชื่อมต่อ MongoDB: {}", e);
+            eprintln!("ไม่สามารถเชื่อมต่อ MongoDB: {e}");
             eprintln!("กรุณาตรวจสอบว่า MongoDB server กำลังทำงานอยู่");
-            return Err(e.into());
+            return Err(e);
         }
     }
 
@@ -57,3 +58,30 @@ pub async fn init() -> mongodb::error::Result<()> {
 pub async fn get_pool() -> &'static MongoPool {
     MONGO.get().expect("MongoDB ยังไม่ได้เริ่มต้น")
 }
+
+// ตรวจว่า migration ที่ต้องทำครั้งเดียวถูกทำไปแล้วหรือยัง (บันทึกไว้ใน collection migrations)
+pub async fn is_migration_applied(name: &str) -> mongodb::error::Result<bool> {
+    let collection = get_pool().await.collection::<Document>("migrations");
+    Ok(collection.find_one(doc! { "_id": name }).await?.is_some())
+}
+
+pub async fn mark_migration_applied(name: &str) -> mongodb::error::Result<()> {
+    let collection = get_pool().await.collection::<Document>("migrations");
+    match collection
+        .insert_one(doc! { "_id": name, "applied_at": mongodb::bson::DateTime::now() })
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) if is_duplicate_key_error(&e) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// ตรวจว่าข้อผิดพลาดเกิดจากการบันทึกข้อมูลซ้ำกับ unique index หรือไม่
+pub fn is_duplicate_key_error(error: &mongodb::error::Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        mongodb::error::ErrorKind::Write(mongodb::error::WriteFailure::WriteError(write_error))
+            if write_error.code == 11000
+    )
+}
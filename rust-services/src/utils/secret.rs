@@ -0,0 +1,58 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::sync::OnceLock;
+
+// เข้ารหัสข้อมูลลับก่อนบันทึกลงฐานข้อมูล เช่น token ของ webhook
+// กุญแจอ่านจาก SECRET_KEY (base64 ขนาด 32 ไบต์) ถ้าไม่ได้ตั้งค่าจะเข้ารหัสและถอดรหัสไม่ได้
+static KEY: OnceLock<Option<LessSafeKey>> = OnceLock::new();
+
+fn key() -> Option<&'static LessSafeKey> {
+    KEY.get_or_init(|| {
+        let encoded = std::env::var("SECRET_KEY").ok()?;
+        let key = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| UnboundKey::new(&CHACHA20_POLY1305, &bytes).ok());
+        if key.is_none() {
+            println!("SECRET_KEY ไม่ถูกต้อง ต้องเป็น base64 ขนาด 32 ไบต์");
+        }
+        key.map(LessSafeKey::new)
+    })
+    .as_ref()
+}
+
+pub fn is_configured() -> bool {
+    key().is_some()
+}
+
+// เข้ารหัสแล้วคืนเป็น base64 ของ nonce ตามด้วยข้อมูลที่เข้ารหัส
+pub fn seal(plaintext: &str) -> Option<String> {
+    let key = key()?;
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new().fill(&mut nonce).ok()?;
+
+    let mut data = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .ok()?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(data);
+    Some(STANDARD.encode(sealed))
+}
+
+// ถอดรหัสค่าที่ได้จาก seal คืน None ถ้ากุญแจไม่ตรงหรือข้อมูลถูกแก้ไข
+pub fn open(sealed: &str) -> Option<String> {
+    let key = key()?;
+    let bytes = STANDARD.decode(sealed).ok()?;
+    if bytes.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, data) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+
+    let mut data = data.to_vec();
+    let plaintext = key.open_in_place(nonce, Aad::empty(), &mut data).ok()?;
+    String::from_utf8(plaintext.to_vec()).ok()
+}
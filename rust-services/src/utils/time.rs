@@ -0,0 +1,22 @@
+use chrono_tz::Tz;
+
+// ชื่อวันในสัปดาห์ เริ่มจากวันจันทร์
+pub const WEEKDAY_NAMES: [&str; 7] = [
+    "วันจันทร์",
+    "วันอังคาร",
+    "วันพุธ",
+    "วันพฤหัสบดี",
+    "วันศุกร์",
+    "วันเสาร์",
+    "วันอาทิตย์",
+];
+
+// แปลงเวลาเป็น timestamp ของ Discord (<t:...>) ซึ่งแสดงตามเขตเวลาของผู้อ่านแต่ละคน
+pub fn discord_timestamp(datetime: &mongodb::bson::DateTime) -> String {
+    format!("<t:{}:f>", datetime.timestamp_millis() / 1000)
+}
+
+// แปลงชื่อเขตเวลาแบบ IANA เช่น "Asia/Bangkok" คืน None ถ้าไม่รู้จัก
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse::<Tz>().ok()
+}
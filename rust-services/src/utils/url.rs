@@ -0,0 +1,55 @@
+use reqwest::Url;
+use url::form_urlencoded;
+
+// query parameter ที่ใช้ติดตามที่มาของลิงก์ ไม่มีผลกับหน้าเว็บ
+const TRACKING_PARAMS: [&str; 9] = [
+    "fbclid", "gclid", "igshid", "ref", "ref_src", "si", "mc_cid", "mc_eid", "_ga",
+];
+
+// แปลง URL ให้อยู่ในรูปแบบมาตรฐาน เพื่อให้ลิงก์ที่ชี้ไปหน้าเดียวกันได้ค่าเดียวกัน
+// https เสมอ, host ตัวพิมพ์เล็กและตัด www., ตัด fragment, ตัด query ที่ใช้ติดตาม และตัด / ท้าย path
+// query ที่เหลือเรียงตามชื่อและเข้ารหัสใหม่ ลำดับ parameter จึงไม่ทำให้ได้ URL ต่างกัน
+// คืน None ถ้าไม่ใช่ URL แบบ http(s)
+pub fn canonicalize(url: &str) -> Option<String> {
+    let parsed = Url::parse(url.trim()).ok()?;
+    if parsed.scheme() != "https" && parsed.scheme() != "http" {
+        return None;
+    }
+
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    let mut canonical = format!("https://{host}");
+    if let Some(port) = parsed.port().filter(|port| *port != 80 && *port != 443) {
+        canonical.push_str(&format!(":{port}"));
+    }
+
+    let path = parsed
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>()
+        .join("/");
+    if !path.is_empty() {
+        canonical.push('/');
+        canonical.push_str(&path);
+    }
+
+    let mut pairs = parsed
+        .query_pairs()
+        .filter(|(key, _)| {
+            let key = key.to_lowercase();
+            !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .collect::<Vec<_>>();
+    if !pairs.is_empty() {
+        pairs.sort();
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        canonical.push('?');
+        canonical.push_str(&query);
+    }
+
+    Some(canonical)
+}